@@ -0,0 +1,95 @@
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::database::Database;
+use crate::session::HeartbeatData;
+
+// Same idea as WakaTime's keystroke timeout: the gap to the next heartbeat counts as
+// coding time unless it is long enough that the user must have stepped away.
+pub const HEARTBEAT_TIMEOUT_SECS: f64 = 15.0 * 60.0;
+
+pub fn heartbeat_durations(heartbeats: &[HeartbeatData]) -> Vec<f64> {
+    heartbeats
+        .iter()
+        .enumerate()
+        .map(|(i, heartbeat)| match heartbeats.get(i + 1) {
+            Some(next) => (next.time - heartbeat.time).clamp(0.0, HEARTBEAT_TIMEOUT_SECS),
+            None => 0.0,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+impl DateRange {
+    // Missing bounds default to the current week so far, Monday to today.
+    pub fn resolve(self) -> (String, String) {
+        let today = chrono::Local::now().date_naive();
+        let week_start = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+        (
+            self.start_date.unwrap_or_else(|| week_start.format("%Y-%m-%d").to_string()),
+            self.end_date.unwrap_or_else(|| today.format("%Y-%m-%d").to_string()),
+        )
+    }
+}
+
+pub fn local_date_range(start_date: &str, end_date: &str) -> Result<(i64, i64), String> {
+    let parse = |value: &str| {
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date '{}': {}", value, e))
+    };
+    let start = parse(start_date)?;
+    let end = parse(end_date)?;
+    if end < start {
+        return Err(format!("End date {} is before start date {}", end_date, start_date));
+    }
+
+    let to_timestamp = |date: chrono::NaiveDate| {
+        date.and_hms_opt(0, 0, 0)
+            .and_then(|dt| dt.and_local_timezone(chrono::Local).earliest())
+            .map(|dt| dt.timestamp())
+            .ok_or_else(|| format!("Invalid local date {}", date))
+    };
+
+    Ok((to_timestamp(start)?, to_timestamp(end + chrono::Duration::days(1))?))
+}
+
+pub fn dates_between(start_date: &str, end_date: &str) -> Result<Vec<String>, String> {
+    let parse = |value: &str| {
+        chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid date '{}': {}", value, e))
+    };
+    let end = parse(end_date)?;
+    Ok(parse(start_date)?
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .collect())
+}
+
+pub async fn local_seconds_by(
+    start: i64,
+    end: i64,
+    key: fn(&HeartbeatData) -> Option<String>,
+) -> Result<HashMap<String, f64>, String> {
+    let db = Database::new().await?;
+    let heartbeats = db.get_heartbeats_between(start, end).await?;
+    let durations = heartbeat_durations(&heartbeats);
+
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for (heartbeat, duration) in heartbeats.iter().zip(durations) {
+        let name = key(heartbeat).unwrap_or_else(|| "Unknown".to_string());
+        *totals.entry(name).or_default() += duration;
+    }
+
+    Ok(totals)
+}
+
+pub async fn local_project_seconds(start: i64, end: i64) -> Result<HashMap<String, f64>, String> {
+    local_seconds_by(start, end, |heartbeat| heartbeat.project.clone()).await
+}
@@ -6,30 +6,61 @@ use sha2::{Sha256, Digest};
 use base64::{Engine as _, engine::general_purpose};
 use rand::Rng;
 
-use crate::database::{AuthState as DbAuthState, Database};
+use crate::database::{account_key_for, AuthState as DbAuthState, Database};
+use crate::auth_flow::{AuthFlow, AuthFlowState, OAUTH_REDIRECT_URI};
+use crate::deep_link::DeepLinkRoute;
 use crate::push_log;
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialType {
+    #[default]
+    #[serde(rename = "oauth")]
+    OAuth,
+    ApiKey,
+}
+
+impl CredentialType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CredentialType::OAuth => "oauth",
+            CredentialType::ApiKey => "api_key",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "api_key" => CredentialType::ApiKey,
+            _ => CredentialType::OAuth,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthState {
     pub is_authenticated: bool,
     pub access_token: Option<String>,
     pub user_info: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    pub credential_type: CredentialType,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PkceState {
     pub code_verifier: String,
     pub state: String,
+    pub redirect_uri: String,
     pub timestamp: i64,
 }
 
 impl PkceState {
-    pub fn new() -> Self {
+    pub fn new(redirect_uri: &str) -> Self {
         let code_verifier = generate_code_verifier();
         let state = generate_state();
         Self {
             code_verifier,
             state,
+            redirect_uri: redirect_uri.to_string(),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
@@ -52,7 +83,7 @@ fn generate_code_verifier() -> String {
     general_purpose::URL_SAFE_NO_PAD.encode(&bytes)
 }
 
-fn generate_code_challenge(verifier: &str) -> String {
+pub(crate) fn generate_code_challenge(verifier: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(verifier.as_bytes());
     let hash = hasher.finalize();
@@ -81,30 +112,15 @@ pub async fn get_auth_state(
 #[tauri::command]
 pub async fn authenticate_with_rails(
     api_config: crate::config::ApiConfig,
-    pkce_state: State<'_, Arc<tauri::async_runtime::Mutex<Option<PkceState>>>>,
+    use_loopback: Option<bool>,
     app_handle: tauri::AppHandle,
 ) -> Result<String, String> {
-
-    let callback_url = "hackatime://auth/callback";
-    
-    let pkce = PkceState::new();
-    let code_challenge = generate_code_challenge(&pkce.code_verifier);
-    
-    {
-        let mut stored_pkce = pkce_state.lock().await;
-        *stored_pkce = Some(pkce.clone());
-    }
-    
-    push_log("debug", "backend", format!("Generated PKCE parameters - verifier: {}, challenge: {}, state: {}", 
-             pkce.code_verifier, code_challenge, pkce.state));
-    
-    let auth_url = format!(
-        "{}/oauth/authorize?client_id=BPr5VekIV-xuQ2ZhmxbGaahJ3XVd7gM83pql-HYGYxQ&redirect_uri={}&response_type=code&scope=profile&state={}&code_challenge={}&code_challenge_method=S256",
-        api_config.base_url,
-        urlencoding::encode(callback_url),
-        urlencoding::encode(&pkce.state),
-        urlencoding::encode(&code_challenge)
-    );
+    let flow = AuthFlow::new(&app_handle, api_config.base_url);
+    let auth_url = if use_loopback.unwrap_or(false) || !crate::deep_link::deep_links_registered() {
+        crate::loopback::begin_loopback_login(flow).await?
+    } else {
+        flow.begin(OAUTH_REDIRECT_URI).await
+    };
 
     // Use Tauri's opener plugin for better cross-platform support
     use tauri_plugin_opener::OpenerExt;
@@ -123,37 +139,94 @@ pub async fn handle_auth_callback(
     token: String,
     state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
 ) -> Result<(), String> {
+    crate::redaction::register_secret(&token);
     let mut auth_state = state.lock().await;
     auth_state.is_authenticated = true;
     auth_state.access_token = Some(token);
     auth_state.user_info = Some(HashMap::new());
+    auth_state.credential_type = CredentialType::OAuth;
 
     Ok(())
 }
 
 #[tauri::command]
 pub async fn logout(
+    remove_wakatime_api_key: Option<bool>,
+    app_handle: tauri::AppHandle,
     state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+    session_state: State<'_, Arc<tauri::async_runtime::Mutex<crate::session::SessionState>>>,
+    discord_rpc: State<'_, Arc<tauri::async_runtime::Mutex<crate::discord_rpc::DiscordRpcService>>>,
 ) -> Result<(), String> {
-    let mut auth_state = state.lock().await;
-    auth_state.is_authenticated = false;
-    auth_state.access_token = None;
-    auth_state.user_info = None;
+    let (account_key, access_token, credential_type) = {
+        let mut auth_state = state.lock().await;
+        let account_key = account_key_for(auth_state.user_info.as_ref(), auth_state.access_token.as_deref());
+        let access_token = auth_state.access_token.take();
+        auth_state.is_authenticated = false;
+        auth_state.user_info = None;
+        (account_key, access_token, std::mem::take(&mut auth_state.credential_type))
+    };
 
-    if let Err(e) = clear_auth_state().await {
+    let flow = AuthFlow::from_app(&app_handle);
+    // API keys are not OAuth tokens, so there is nothing to revoke for them.
+    if let (Some(token), CredentialType::OAuth) = (&access_token, credential_type) {
+        if let Err(e) = flow.revoke(token).await {
+            push_log("warn", "backend", format!("Failed to revoke access token: {}", e));
+        }
+    }
+    flow.transition(AuthFlowState::Idle).await;
+
+    if let Err(e) = forget_account(&account_key).await {
         push_log("error", "backend", format!("Failed to clear auth state: {}", e));
     }
 
     
-    push_log("info", "backend", "Clearing statistics cache on logout...".to_string());
+    push_log("info", "backend", "Clearing cached data on logout...".to_string());
     if let Ok(db) = Database::new().await {
         if let Err(e) = db.clear_all_cache().await {
             push_log("error", "backend", format!("Failed to clear statistics cache on logout: {}", e));
-        } else {
-            push_log("info", "backend", "Statistics cache cleared on logout".to_string());
         }
+        if let Err(e) = db.clear_heartbeats().await {
+            push_log("error", "backend", format!("Failed to clear recorded heartbeats on logout: {}", e));
+        }
+    }
+
+    {
+        let mut session = session_state.lock().await;
+        session.is_active = false;
+        session.start_time = None;
+        session.last_heartbeat_id = None;
+        session.heartbeat_count = 0;
+        session.project = None;
+        session.editor = None;
+        session.language = None;
+        session.entity = None;
+        session.repository = None;
+    }
+
+    {
+        let mut discord = discord_rpc.lock().await;
+        if discord.is_connected() {
+            if let Err(e) = discord.clear_activity() {
+                push_log("warn", "backend", format!("Failed to clear Discord activity on logout: {}", e));
+            }
+        }
+    }
+
+    crate::tray::refresh_tray_status(&app_handle).await;
+
+    // The frontend asks the user before passing this, since other WakaTime clients on the
+    // machine stop sending heartbeats once the key is gone.
+    if remove_wakatime_api_key.unwrap_or(false) {
+        let config_path = crate::setup::get_wakatime_config_path()?;
+        if let Some(backup_path) = crate::setup::backup_wakatime_config(&config_path)? {
+            push_log("info", "backend", format!("Backed up existing config to {}", backup_path));
+        }
+        crate::setup::set_wakatime_setting("api_key", None)?;
+        push_log("info", "backend", format!("Removed api_key from {}", config_path));
     }
 
+    push_log("info", "backend", "Logged out".to_string());
+    crate::redaction::clear_secrets();
     Ok(())
 }
 
@@ -162,10 +235,12 @@ pub async fn test_auth_callback(
     token: String,
     state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
 ) -> Result<(), String> {
+    crate::redaction::register_secret(&token);
     let mut auth_state = state.lock().await;
     auth_state.is_authenticated = true;
     auth_state.access_token = Some(token);
     auth_state.user_info = Some(HashMap::new());
+    auth_state.credential_type = CredentialType::OAuth;
 
     Ok(())
 }
@@ -192,7 +267,20 @@ pub async fn get_api_key(
         .as_ref()
         .ok_or("No access token available")?;
 
+    // An API key login already holds the key itself.
+    if auth_state.credential_type == CredentialType::ApiKey {
+        return Ok(access_token.clone());
+    }
+
     let client = reqwest::Client::new();
+    fetch_api_key(&client, base_url, access_token).await
+}
+
+pub async fn fetch_api_key(
+    client: &reqwest::Client,
+    base_url: &str,
+    access_token: &str,
+) -> Result<String, String> {
     let response = client
         .get(&format!("{}/api/v1/authenticated/api_keys", base_url))
         .bearer_auth(access_token)
@@ -216,6 +304,7 @@ pub async fn get_api_key(
     let api_key = api_key_response["token"]
         .as_str()
         .ok_or("No token in response")?;
+    crate::redaction::register_secret(api_key);
 
     Ok(api_key.to_string())
 }
@@ -224,177 +313,42 @@ pub async fn get_api_key(
 pub async fn authenticate_with_direct_oauth(
     oauth_token: String,
     api_config: crate::config::ApiConfig,
-    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let client = reqwest::Client::new();
+    let flow = AuthFlow::new(&app_handle, api_config.base_url);
 
-    if oauth_token.starts_with("hackatime://auth/callback") {
-        if let Some(query_start) = oauth_token.find('?') {
-            let query = &oauth_token[query_start + 1..];
-            let params: Vec<&str> = query.split('&').collect();
-
-            let mut found_code = None;
-            let mut found_state = None;
-            let mut found_error = None;
-            
-            for param in params {
-                if param.starts_with("code=") {
-                    found_code = Some(param[5..].to_string()); 
-                } else if param.starts_with("state=") {
-                    found_state = Some(param[6..].to_string()); 
-                } else if param.starts_with("error=") {
-                    found_error = Some(param[6..].to_string());
-                }
-            }
-
-            if let Some(error) = found_error {
-                return Err(format!("OAuth error: {}", error));
-            }
-
-            if let Some(code) = found_code {
-                push_log("debug", "backend", format!("Extracted authorization code from deep link: {}", code));
-                
-                return exchange_authorization_code(code, found_state, api_config, state, client).await;
-            } else {
-                return Err("No authorization code found in deep link URL".to_string());
-            }
-        } else {
-            return Err("Invalid deep link URL format".to_string());
-        }
-    } else {
-        return validate_access_token(oauth_token, api_config, state, client).await;
+    if !oauth_token.starts_with(&format!("{}://", crate::deep_link::DEEP_LINK_SCHEME)) {
+        push_log("info", "backend", "Validating access token directly".to_string());
+        return flow.authenticate_with_token(oauth_token, CredentialType::OAuth).await;
     }
-}
-
-async fn exchange_authorization_code(
-    code: String,
-    _state: Option<String>,
-    api_config: crate::config::ApiConfig,
-    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
-    client: reqwest::Client,
-) -> Result<(), String> {
-    push_log("info", "backend", "Exchanging authorization code for access token".to_string());
-    
-    let response = client
-        .post(&format!(
-            "{}/oauth/token",
-            api_config.base_url
-        ))
-        .form(&[
-            ("grant_type", "authorization_code"),
-            ("code", &code),
-            ("client_id", "BPr5VekIV-xuQ2ZhmxbGaahJ3XVd7gM83pql-HYGYxQ"),
-            ("redirect_uri", "hackatime://auth/callback"),
-        ])
-        .send()
-        .await
-        .map_err(|e| format!("Failed to exchange authorization code: {}", e))?;
-
-    push_log("debug", "backend", format!("Token exchange response status: {}", response.status()));
-
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        push_log("error", "backend", format!("Token exchange failed with error: {}", error_text));
-        return Err(format!("Token exchange failed: {}", error_text));
-    }
-
-    let token_response: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse token response: {}", e))?;
-
-    let access_token = token_response["access_token"]
-        .as_str()
-        .ok_or("No access token in response")?;
-
-    let user_response = client
-        .get(&format!("{}/api/v1/authenticated/me", api_config.base_url))
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch user info: {}", e))?;
-
-    let user_info = if user_response.status().is_success() {
-        user_response.json::<serde_json::Value>()
-            .await
-            .unwrap_or_else(|_| serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
 
-    let mut user_info_map = HashMap::new();
-    if let Some(obj) = user_info.as_object() {
-        for (key, value) in obj {
-            user_info_map.insert(key.clone(), value.clone());
+    match crate::deep_link::parse_deep_link(&oauth_token) {
+        Ok(DeepLinkRoute::AuthCallback { error: Some(error), .. }) => Err(flow.fail(format!("OAuth error: {}", error)).await),
+        Ok(DeepLinkRoute::AuthCallback { code: Some(code), state, .. }) => {
+            push_log("debug", "backend", "Extracted authorization code from deep link".to_string());
+            flow.complete(&code, state.as_deref()).await
         }
+        Ok(DeepLinkRoute::AuthCallback { .. }) => Err(flow.fail("No authorization code found in deep link URL".to_string()).await),
+        Ok(_) => Err("Deep link is not an authentication callback".to_string()),
+        Err(e) => Err(e),
     }
-
-    let mut auth_state = auth_state.lock().await;
-    auth_state.is_authenticated = true;
-    auth_state.access_token = Some(access_token.to_string());
-    auth_state.user_info = Some(user_info_map);
-
-    let auth_state_to_save = auth_state.clone();
-    drop(auth_state); 
-    if let Err(e) = save_auth_state(auth_state_to_save).await {
-        push_log("error", "backend", format!("Failed to save auth state: {}", e));
-    }
-
-    push_log("info", "backend", "Direct OAuth authentication completed successfully!".to_string());
-    Ok(())
 }
 
-async fn validate_access_token(
-    access_token: String,
+#[tauri::command]
+pub async fn login_with_api_key(
+    api_key: String,
     api_config: crate::config::ApiConfig,
-    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
-    client: reqwest::Client,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    push_log("info", "backend", "Validating access token directly".to_string());
-    
-    let response = client
-        .get(&format!("{}/api/v1/authenticated/me", api_config.base_url))
-        .bearer_auth(&access_token)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to validate access token: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("Access token validation failed: {}", error_text));
+    let api_key = api_key.trim().to_string();
+    if api_key.is_empty() {
+        return Err("API key must not be empty".to_string());
     }
 
-    let user_info = response
-        .json::<serde_json::Value>()
+    push_log("info", "backend", "Validating pasted API key".to_string());
+    AuthFlow::new(&app_handle, api_config.base_url)
+        .authenticate_with_token(api_key, CredentialType::ApiKey)
         .await
-        .map_err(|e| format!("Failed to parse user info response: {}", e))?;
-
-    let mut user_info_map = HashMap::new();
-    if let Some(obj) = user_info.as_object() {
-        for (key, value) in obj {
-            user_info_map.insert(key.clone(), value.clone());
-        }
-    }
-
-    let mut auth_state = auth_state.lock().await;
-    auth_state.is_authenticated = true;
-    auth_state.access_token = Some(access_token);
-    auth_state.user_info = Some(user_info_map);
-
-    let auth_state_to_save = auth_state.clone();
-    drop(auth_state); 
-    if let Err(e) = save_auth_state(auth_state_to_save).await {
-        push_log("error", "backend", format!("Failed to save auth state: {}", e));
-    }
-
-    push_log("info", "backend", "Access token validation completed successfully!".to_string());
-    Ok(())
 }
 
 #[tauri::command]
@@ -402,103 +356,11 @@ pub async fn handle_deep_link_callback(
     authorization_code: String,
     state: String,
     api_config: crate::config::ApiConfig,
-    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
-    pkce_state: State<'_, Arc<tauri::async_runtime::Mutex<Option<PkceState>>>>,
+    app_handle: tauri::AppHandle,
 ) -> Result<(), String> {
-    let stored_pkce = {
-        let pkce_guard = pkce_state.lock().await;
-        pkce_guard.clone()
-    };
-
-    let pkce = match stored_pkce {
-        Some(pkce) => {
-            if pkce.is_expired(600) {
-                return Err("PKCE state expired. Please restart authentication.".to_string());
-            }
-            
-            if pkce.state != state {
-                return Err("State parameter mismatch. Possible CSRF attack.".to_string());
-            }
-            
-            pkce
-        }
-        None => return Err("No PKCE state found. Please restart authentication.".to_string()),
-    };
-
-    let client = reqwest::Client::new();
-    let response = client
-        .post(&format!(
-            "{}/oauth/token",
-            api_config.base_url
-        ))
-        .form(&[
-            ("grant_type", "authorization_code"),
-            ("code", &authorization_code),
-            ("client_id", "BPr5VekIV-xuQ2ZhmxbGaahJ3XVd7gM83pql-HYGYxQ"),
-            ("redirect_uri", "hackatime://auth/callback"),
-            ("code_verifier", &pkce.code_verifier),
-        ])
-        .send()
+    AuthFlow::new(&app_handle, api_config.base_url)
+        .complete(&authorization_code, Some(&state))
         .await
-        .map_err(|e| format!("Failed to exchange token: {}", e))?;
-
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("Token exchange failed: {}", error_text));
-    }
-
-    let token_response: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse token response: {}", e))?;
-
-    let access_token = token_response["access_token"]
-        .as_str()
-        .ok_or("No access token in response")?;
-
-    let user_response = client
-        .get(&format!("{}/api/v1/authenticated/me", api_config.base_url))
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch user info: {}", e))?;
-
-    let user_info = if user_response.status().is_success() {
-        user_response.json::<serde_json::Value>()
-            .await
-            .unwrap_or_else(|_| serde_json::json!({}))
-    } else {
-        serde_json::json!({})
-    };
-
-    let mut user_info_map = HashMap::new();
-    if let Some(obj) = user_info.as_object() {
-        for (key, value) in obj {
-            user_info_map.insert(key.clone(), value.clone());
-        }
-    }
-
-    let mut auth_state = auth_state.lock().await;
-    auth_state.is_authenticated = true;
-    auth_state.access_token = Some(access_token.to_string());
-    auth_state.user_info = Some(user_info_map);
-
-    let auth_state_to_save = auth_state.clone();
-    drop(auth_state); 
-    if let Err(e) = save_auth_state(auth_state_to_save).await {
-        push_log("error", "backend", format!("Failed to save auth state: {}", e));
-    }
-
-    {
-        let mut stored_pkce = pkce_state.lock().await;
-        *stored_pkce = None;
-    }
-
-    push_log("info", "backend", "OAuth authentication completed successfully!".to_string());
-    Ok(())
 }
 
 #[tauri::command]
@@ -516,6 +378,7 @@ pub async fn save_auth_state(auth_state: AuthState) -> Result<(), String> {
         is_authenticated: auth_state.is_authenticated,
         access_token: auth_state.access_token,
         user_info: auth_state.user_info,
+        credential_type: auth_state.credential_type.as_str().to_string(),
     };
 
     
@@ -538,10 +401,14 @@ pub async fn load_auth_state() -> Result<Option<AuthState>, String> {
                 db_auth_state.is_authenticated,
                 db_auth_state.access_token.is_some()
             ));
+            if let Some(token) = &db_auth_state.access_token {
+                crate::redaction::register_secret(token);
+            }
             let auth_state = AuthState {
                 is_authenticated: db_auth_state.is_authenticated,
                 access_token: db_auth_state.access_token,
                 user_info: db_auth_state.user_info,
+                credential_type: CredentialType::from_db(&db_auth_state.credential_type),
             };
             Ok(Some(auth_state))
         }
@@ -553,9 +420,22 @@ pub async fn load_auth_state() -> Result<Option<AuthState>, String> {
 }
 
 #[tauri::command]
-pub async fn clear_auth_state() -> Result<(), String> {
+pub async fn clear_auth_state(
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<(), String> {
+    let account_key = {
+        let auth_state = state.lock().await;
+        account_key_for(auth_state.user_info.as_ref(), auth_state.access_token.as_deref())
+    };
+    forget_account(&account_key).await
+}
+
+// Only the signed-in account's row goes; other saved accounts stay available to switch to.
+async fn forget_account(account_key: &str) -> Result<(), String> {
     let db = Database::new().await?;
-    db.clear_sessions().await?;
+    if db.delete_session_for_account(account_key).await? {
+        push_log("info", "backend", format!("Removed saved session for {}", account_key));
+    }
     Ok(())
 }
 
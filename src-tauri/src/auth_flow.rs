@@ -0,0 +1,486 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::auth::{generate_code_challenge, save_auth_state, AuthState, CredentialType, PkceState};
+use crate::config::ApiConfig;
+use crate::push_log;
+
+pub const OAUTH_CLIENT_ID: &str = "BPr5VekIV-xuQ2ZhmxbGaahJ3XVd7gM83pql-HYGYxQ";
+pub const OAUTH_REDIRECT_URI: &str = "hackatime://auth/callback";
+pub const PKCE_MAX_AGE_SECONDS: i64 = 600;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum AuthFlowState {
+    Idle,
+    AwaitingCallback { started_at: i64 },
+    Exchanging,
+    Authenticated,
+    Failed { error: String },
+}
+
+pub type AuthFlowHandle = Arc<tauri::async_runtime::Mutex<AuthFlowState>>;
+pub type AuthStateHandle = Arc<tauri::async_runtime::Mutex<AuthState>>;
+pub type PkceStateHandle = Arc<tauri::async_runtime::Mutex<Option<PkceState>>>;
+
+// Backoff for refetching the profile when it couldn't be loaded right after login.
+const USER_INFO_RETRY_DELAYS_SECS: [u64; 4] = [15, 60, 300, 900];
+
+#[derive(Clone)]
+pub struct AuthFlow {
+    app: Option<AppHandle>,
+    base_url: String,
+    client: reqwest::Client,
+    auth_state: AuthStateHandle,
+    pkce_state: PkceStateHandle,
+    flow_state: AuthFlowHandle,
+}
+
+impl AuthFlow {
+    pub fn new(app: &AppHandle, base_url: impl Into<String>) -> Self {
+        let mut flow = Self::with_state(
+            base_url,
+            reqwest::Client::new(),
+            app.state::<AuthStateHandle>().inner().clone(),
+            app.state::<PkceStateHandle>().inner().clone(),
+            app.state::<AuthFlowHandle>().inner().clone(),
+        );
+        flow.app = Some(app.clone());
+        flow
+    }
+
+    pub fn from_app(app: &AppHandle) -> Self {
+        let base_url = app.state::<ApiConfig>().base_url.clone();
+        Self::new(app, base_url)
+    }
+
+    // Without an app handle the flow works the same but emits no events.
+    pub fn with_state(
+        base_url: impl Into<String>,
+        client: reqwest::Client,
+        auth_state: AuthStateHandle,
+        pkce_state: PkceStateHandle,
+        flow_state: AuthFlowHandle,
+    ) -> Self {
+        Self {
+            app: None,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            client,
+            auth_state,
+            pkce_state,
+            flow_state,
+        }
+    }
+
+    pub async fn transition(&self, next: AuthFlowState) {
+        *self.flow_state.lock().await = next.clone();
+
+        match &next {
+            AuthFlowState::Failed { error } => push_log("error", "backend", format!("Authentication failed: {}", error)),
+            other => push_log("debug", "backend", format!("Auth flow state: {:?}", other)),
+        }
+        if let Some(app) = &self.app {
+            if let Err(e) = app.emit("auth-flow-state", &next) {
+                push_log("warn", "backend", format!("Failed to emit auth flow state: {}", e));
+            }
+        }
+    }
+
+    pub async fn begin(&self, redirect_uri: &str) -> String {
+        let pkce = PkceState::new(redirect_uri);
+        let code_challenge = generate_code_challenge(&pkce.code_verifier);
+
+        crate::redaction::register_secret(&pkce.code_verifier);
+        crate::redaction::register_secret(&pkce.state);
+        push_log("debug", "backend", "Generated PKCE parameters".to_string());
+
+        let auth_url = format!(
+            "{}/oauth/authorize?client_id={}&redirect_uri={}&response_type=code&scope=profile&state={}&code_challenge={}&code_challenge_method=S256",
+            self.base_url,
+            OAUTH_CLIENT_ID,
+            urlencoding::encode(redirect_uri),
+            urlencoding::encode(&pkce.state),
+            urlencoding::encode(&code_challenge)
+        );
+
+        let started_at = pkce.timestamp;
+        *self.pkce_state.lock().await = Some(pkce);
+        self.transition(AuthFlowState::AwaitingCallback { started_at }).await;
+
+        auth_url
+    }
+
+    pub async fn fail(&self, error: String) -> String {
+        self.transition(AuthFlowState::Failed { error: error.clone() }).await;
+        error
+    }
+
+    pub async fn complete(&self, code: &str, state: Option<&str>) -> Result<(), String> {
+        self.transition(AuthFlowState::Exchanging).await;
+
+        let result = match self.take_pkce(state).await {
+            Ok(pkce) => self.exchange_code(code, &pkce).await,
+            Err(e) => Err(e),
+        };
+
+        let access_token = match result {
+            Ok(access_token) => access_token,
+            Err(e) => return Err(self.fail(e).await),
+        };
+
+        // The server just issued this token, so a failing profile request is no reason to
+        // throw it away: log in with an empty profile and fetch it again later.
+        let user_info = match self.fetch_user_info(&access_token).await {
+            Ok(user_info) => user_info,
+            Err(e) => {
+                push_log("warn", "backend", format!("Logged in without profile, will retry: {}", e));
+                self.retry_user_info(access_token.clone());
+                HashMap::new()
+            }
+        };
+        self.finish(access_token, CredentialType::OAuth, user_info).await
+    }
+
+    pub async fn handle_callback(&self, code: Option<String>, state: Option<String>, error: Option<String>) -> Result<(), String> {
+        if let Some(error) = error {
+            return Err(self.fail(format!("OAuth error: {}", error)).await);
+        }
+        let Some(code) = code else {
+            return Err(self.fail("Missing authorization code in OAuth callback".to_string()).await);
+        };
+
+        push_log("info", "backend", "Received OAuth callback with authorization code and state".to_string());
+        self.complete(&code, state.as_deref()).await
+    }
+
+    pub async fn authenticate_with_token(&self, token: String, credential_type: CredentialType) -> Result<(), String> {
+        self.transition(AuthFlowState::Exchanging).await;
+        crate::redaction::register_secret(&token);
+
+        // A pasted token is only accepted once the server confirms it.
+        let user_info = match self.fetch_user_info(&token).await {
+            Ok(user_info) => user_info,
+            Err(e) => return Err(self.fail(e).await),
+        };
+        self.finish(token, credential_type, user_info).await
+    }
+
+    pub async fn revoke(&self, token: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .post(format!("{}/oauth/revoke", self.base_url))
+            .form(&[("token", token), ("client_id", OAUTH_CLIENT_ID)])
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach revocation endpoint: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("Revocation endpoint returned {}", response.status()));
+        }
+
+        push_log("info", "backend", "Revoked access token".to_string());
+        Ok(())
+    }
+
+    // True while a login is pending and the callback carries some other request's state.
+    pub(crate) async fn is_foreign_state(&self, state: Option<&str>) -> bool {
+        self.pkce_state
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|pkce| !pkce.is_expired(PKCE_MAX_AGE_SECONDS) && state != Some(pkce.state.as_str()))
+    }
+
+    async fn take_pkce(&self, state: Option<&str>) -> Result<PkceState, String> {
+        let mut stored = self.pkce_state.lock().await;
+
+        let Some(pkce) = stored.as_ref() else {
+            return Err("No PKCE state found. Please restart authentication.".to_string());
+        };
+        if pkce.is_expired(PKCE_MAX_AGE_SECONDS) {
+            *stored = None;
+            return Err("PKCE state expired. Please restart authentication.".to_string());
+        }
+        // A mismatched callback may come from someone else's link, so keep the pending
+        // login alive for the genuine callback instead of clearing it.
+        if state != Some(pkce.state.as_str()) {
+            return Err("State parameter mismatch. Possible CSRF attack.".to_string());
+        }
+
+        stored.take().ok_or_else(|| "No PKCE state found. Please restart authentication.".to_string())
+    }
+
+    async fn exchange_code(&self, code: &str, pkce: &PkceState) -> Result<String, String> {
+        push_log("info", "backend", "Exchanging authorization code for access token".to_string());
+
+        let response = self
+            .client
+            .post(format!("{}/oauth/token", self.base_url))
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("client_id", OAUTH_CLIENT_ID),
+                ("redirect_uri", pkce.redirect_uri.as_str()),
+                ("code_verifier", pkce.code_verifier.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| format!("Failed to exchange authorization code: {}", e))?;
+
+        push_log("debug", "backend", format!("Token exchange response status: {}", response.status()));
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Token exchange failed: {}", error_text));
+        }
+
+        let token_response: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse token response: {}", e))?;
+
+        let access_token = token_response["access_token"]
+            .as_str()
+            .ok_or("No access token in response")?;
+        crate::redaction::register_secret(access_token);
+
+        Ok(access_token.to_string())
+    }
+
+    async fn fetch_user_info(&self, access_token: &str) -> Result<HashMap<String, serde_json::Value>, String> {
+        let response = self
+            .client
+            .get(format!("{}/api/v1/authenticated/me", self.base_url))
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch user info: {}", e))?;
+
+        if !response.status().is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Access token validation failed: {}", error_text));
+        }
+
+        let user_info = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| format!("Failed to parse user info response: {}", e))?;
+
+        Ok(user_info
+            .as_object()
+            .map(|obj| obj.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default())
+    }
+
+    async fn finish(
+        &self,
+        access_token: String,
+        credential_type: CredentialType,
+        user_info: HashMap<String, serde_json::Value>,
+    ) -> Result<(), String> {
+        let auth_state_to_save = {
+            let mut auth_state = self.auth_state.lock().await;
+            auth_state.is_authenticated = true;
+            auth_state.access_token = Some(access_token);
+            auth_state.user_info = Some(user_info);
+            auth_state.credential_type = credential_type;
+            auth_state.clone()
+        };
+
+        if let Err(e) = save_auth_state(auth_state_to_save).await {
+            push_log("error", "backend", format!("Failed to save auth state: {}", e));
+        }
+
+        self.transition(AuthFlowState::Authenticated).await;
+        push_log("info", "backend", format!("Authentication completed successfully using {}", credential_type.as_str()));
+        Ok(())
+    }
+
+    fn retry_user_info(&self, access_token: String) {
+        let flow = self.clone();
+        tauri::async_runtime::spawn(async move {
+            for delay in USER_INFO_RETRY_DELAYS_SECS {
+                tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+
+                // Stop once the user logged out or signed in with another token.
+                if flow.auth_state.lock().await.access_token.as_deref() != Some(access_token.as_str()) {
+                    return;
+                }
+
+                match flow.fetch_user_info(&access_token).await {
+                    Ok(user_info) => {
+                        let auth_state_to_save = {
+                            let mut auth_state = flow.auth_state.lock().await;
+                            if auth_state.access_token.as_deref() != Some(access_token.as_str()) {
+                                return;
+                            }
+                            auth_state.user_info = Some(user_info);
+                            auth_state.clone()
+                        };
+                        if let Err(e) = save_auth_state(auth_state_to_save).await {
+                            push_log("error", "backend", format!("Failed to save auth state: {}", e));
+                        }
+                        push_log("info", "backend", "Loaded user profile after retry".to_string());
+                        flow.transition(AuthFlowState::Authenticated).await;
+                        return;
+                    }
+                    Err(e) => push_log("warn", "backend", format!("Retrying user profile fetch failed: {}", e)),
+                }
+            }
+        });
+    }
+}
+
+#[tauri::command]
+pub async fn get_auth_flow_state(flow_state: State<'_, AuthFlowHandle>) -> Result<AuthFlowState, String> {
+    Ok(flow_state.lock().await.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn flow_for(server: &MockServer) -> AuthFlow {
+        AuthFlow::with_state(
+            server.uri(),
+            reqwest::Client::new(),
+            Arc::new(tauri::async_runtime::Mutex::new(AuthState {
+                is_authenticated: false,
+                access_token: None,
+                user_info: None,
+                credential_type: CredentialType::default(),
+            })),
+            Arc::new(tauri::async_runtime::Mutex::new(None)),
+            Arc::new(tauri::async_runtime::Mutex::new(AuthFlowState::Idle)),
+        )
+    }
+
+    async fn mount_token(server: &MockServer, expected_calls: u64) {
+        Mock::given(method("POST"))
+            .and(path("/oauth/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "access_token": "mock-access-token" })))
+            .expect(expected_calls)
+            .mount(server)
+            .await;
+    }
+
+    fn run(test: impl std::future::Future<Output = ()>) {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(test);
+    }
+
+    #[test]
+    fn state_mismatch_fails_and_keeps_the_pending_login() {
+        run(async {
+            let server = MockServer::start().await;
+            mount_token(&server, 0).await;
+            let flow = flow_for(&server);
+            flow.begin(OAUTH_REDIRECT_URI).await;
+
+            let result = flow.complete("code", Some("forged-state")).await;
+
+            assert!(result.unwrap_err().contains("State parameter mismatch"));
+            assert!(matches!(*flow.flow_state.lock().await, AuthFlowState::Failed { .. }));
+            assert!(flow.pkce_state.lock().await.is_some());
+            assert!(!flow.auth_state.lock().await.is_authenticated);
+        });
+    }
+
+    #[test]
+    fn expired_verifier_is_cleared_without_contacting_the_server() {
+        run(async {
+            let server = MockServer::start().await;
+            mount_token(&server, 0).await;
+            let flow = flow_for(&server);
+            flow.begin(OAUTH_REDIRECT_URI).await;
+
+            let state = {
+                let mut pkce = flow.pkce_state.lock().await;
+                let pkce = pkce.as_mut().unwrap();
+                pkce.timestamp -= PKCE_MAX_AGE_SECONDS + 1;
+                pkce.state.clone()
+            };
+
+            let result = flow.complete("code", Some(&state)).await;
+
+            assert!(result.unwrap_err().contains("expired"));
+            assert!(flow.pkce_state.lock().await.is_none());
+            assert!(matches!(*flow.flow_state.lock().await, AuthFlowState::Failed { .. }));
+        });
+    }
+
+    #[test]
+    fn error_callback_fails_without_exchanging_a_code() {
+        run(async {
+            let server = MockServer::start().await;
+            mount_token(&server, 0).await;
+            let flow = flow_for(&server);
+            flow.begin(OAUTH_REDIRECT_URI).await;
+
+            let result = flow
+                .handle_callback(Some("code".to_string()), None, Some("access_denied".to_string()))
+                .await;
+
+            assert_eq!(result.unwrap_err(), "OAuth error: access_denied");
+            assert_eq!(
+                *flow.flow_state.lock().await,
+                AuthFlowState::Failed { error: "OAuth error: access_denied".to_string() }
+            );
+        });
+    }
+
+    #[test]
+    fn token_is_kept_when_user_info_fails_after_the_exchange() {
+        run(async {
+            let server = MockServer::start().await;
+            mount_token(&server, 1).await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/authenticated/me"))
+                .respond_with(ResponseTemplate::new(500))
+                .mount(&server)
+                .await;
+            let flow = flow_for(&server);
+            flow.begin(OAUTH_REDIRECT_URI).await;
+            let state = flow.pkce_state.lock().await.as_ref().unwrap().state.clone();
+
+            flow.handle_callback(Some("code".to_string()), Some(state), None)
+                .await
+                .expect("login should succeed without a profile");
+
+            let auth_state = flow.auth_state.lock().await.clone();
+            assert!(auth_state.is_authenticated);
+            assert_eq!(auth_state.access_token.as_deref(), Some("mock-access-token"));
+            assert_eq!(auth_state.user_info, Some(HashMap::new()));
+            assert_eq!(auth_state.credential_type, CredentialType::OAuth);
+            assert_eq!(*flow.flow_state.lock().await, AuthFlowState::Authenticated);
+        });
+    }
+
+    #[test]
+    fn pasted_token_is_rejected_when_the_server_does_not_confirm_it() {
+        run(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/authenticated/me"))
+                .respond_with(ResponseTemplate::new(401).set_body_string("unauthorized"))
+                .mount(&server)
+                .await;
+            let flow = flow_for(&server);
+
+            let result = flow.authenticate_with_token("bad-token".to_string(), CredentialType::ApiKey).await;
+
+            assert!(result.unwrap_err().contains("Access token validation failed"));
+            assert!(!flow.auth_state.lock().await.is_authenticated);
+        });
+    }
+}
@@ -0,0 +1,316 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::activity::{heartbeat_durations, local_date_range, DateRange};
+use crate::auth::AuthState;
+use crate::config::ApiConfig;
+use crate::database::Database;
+use crate::projects::{access_token, fetch_json, resolve_base_url};
+use crate::push_log;
+
+const UNKNOWN_BRANCH: &str = "(unknown)";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakdownSource {
+    Server,
+    Local,
+}
+
+impl BreakdownSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BreakdownSource::Server => "server",
+            BreakdownSource::Local => "local",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileNode {
+    pub name: String,
+    pub path: String,
+    pub total_seconds: f64,
+    pub children: Vec<FileNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFilesResponse {
+    pub project: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub source: BreakdownSource,
+    // Absolute directory every node path is relative to, when the entities share one.
+    pub root_path: Option<String>,
+    pub tree: FileNode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchTime {
+    pub name: String,
+    pub total_seconds: f64,
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBranchesResponse {
+    pub project: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub source: BreakdownSource,
+    pub total_seconds: f64,
+    pub branches: Vec<BranchTime>,
+}
+
+impl FileNode {
+    fn new(name: &str, path: String) -> Self {
+        Self {
+            name: name.to_string(),
+            path,
+            total_seconds: 0.0,
+            children: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, components: &[&str], seconds: f64) {
+        self.total_seconds += seconds;
+        let Some((first, rest)) = components.split_first() else {
+            return;
+        };
+
+        let index = match self.children.iter().position(|child| child.name == *first) {
+            Some(index) => index,
+            None => {
+                let path = if self.path.is_empty() {
+                    first.to_string()
+                } else {
+                    format!("{}/{}", self.path, first)
+                };
+                self.children.push(FileNode::new(first, path));
+                self.children.len() - 1
+            }
+        };
+        self.children[index].insert(rest, seconds);
+    }
+
+    fn sort(&mut self) {
+        self.children.sort_by(|a, b| b.total_seconds.total_cmp(&a.total_seconds));
+        for child in &mut self.children {
+            child.sort();
+        }
+    }
+}
+
+fn split_path(entity: &str) -> Vec<&str> {
+    entity.split(['/', '\\']).filter(|part| !part.is_empty()).collect()
+}
+
+fn build_file_tree(project: &str, entities: &HashMap<String, f64>) -> (Option<String>, FileNode) {
+    let paths: Vec<(Vec<&str>, f64)> = entities
+        .iter()
+        .filter(|(_, seconds)| **seconds > 0.0)
+        .map(|(entity, seconds)| (split_path(entity), *seconds))
+        .filter(|(components, _)| !components.is_empty())
+        .collect();
+    let absolute = entities.keys().any(|entity| entity.starts_with('/'));
+
+    // Strip the directory shared by every entity so the tree starts at the project root
+    // rather than at `/home/...`. The last component is always a file, so never strip it.
+    let mut prefix_len = paths
+        .iter()
+        .map(|(components, _)| components.len() - 1)
+        .min()
+        .unwrap_or(0);
+    if let Some((first, _)) = paths.first() {
+        for (components, _) in &paths[1..] {
+            prefix_len = prefix_len.min(first.iter().zip(components).take_while(|(a, b)| a == b).count());
+        }
+    }
+
+    let root_path = paths.first().filter(|_| prefix_len > 0).map(|(components, _)| {
+        let joined = components[..prefix_len].join("/");
+        if absolute {
+            format!("/{}", joined)
+        } else {
+            joined
+        }
+    });
+
+    let mut root = FileNode::new(project, String::new());
+    for (components, seconds) in &paths {
+        root.insert(&components[prefix_len..], *seconds);
+    }
+    root.sort();
+
+    (root_path, root)
+}
+
+fn branch_times(branches: HashMap<String, f64>) -> (f64, Vec<BranchTime>) {
+    let total_seconds: f64 = branches.values().sum();
+    let mut branches: Vec<BranchTime> = branches
+        .into_iter()
+        .filter(|(_, seconds)| *seconds > 0.0)
+        .map(|(name, seconds)| BranchTime {
+            name,
+            total_seconds: seconds,
+            percent: seconds / total_seconds * 100.0,
+        })
+        .collect();
+    branches.sort_by(|a, b| b.total_seconds.total_cmp(&a.total_seconds));
+    (total_seconds, branches)
+}
+
+// WakaTime-compatible summaries carry per-day `projects` and `languages` lists, and
+// `entities` and `branches` as well once they are filtered to a single project.
+pub(crate) async fn fetch_summaries(
+    base_url: &str,
+    access_token: &str,
+    project: Option<&str>,
+    start_date: &str,
+    end_date: &str,
+) -> Result<serde_json::Value, String> {
+    let mut url = format!(
+        "{}/api/v1/users/current/summaries?start={}&end={}",
+        base_url, start_date, end_date
+    );
+    if let Some(project) = project {
+        url.push_str(&format!("&project={}", urlencoding::encode(project)));
+    }
+    fetch_json(&url, access_token, "summaries").await
+}
+
+// Adds up one of the per-day lists across the whole range.
+pub(crate) fn summary_totals(summaries: &serde_json::Value, key: &str) -> HashMap<String, f64> {
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for day in summaries["data"].as_array().into_iter().flatten() {
+        for item in day[key].as_array().into_iter().flatten() {
+            let (Some(name), Some(seconds)) = (item["name"].as_str(), item["total_seconds"].as_f64()) else {
+                continue;
+            };
+            *totals.entry(name.to_string()).or_default() += seconds;
+        }
+    }
+    totals
+}
+
+pub(crate) async fn fetch_summary_totals(
+    base_url: &str,
+    access_token: &str,
+    project: Option<&str>,
+    start_date: &str,
+    end_date: &str,
+    key: &str,
+) -> Result<HashMap<String, f64>, String> {
+    let summaries = fetch_summaries(base_url, access_token, project, start_date, end_date).await?;
+    Ok(summary_totals(&summaries, key))
+}
+
+async fn local_breakdown(
+    project: &str,
+    start_date: &str,
+    end_date: &str,
+    key: fn(&crate::session::HeartbeatData) -> Option<String>,
+) -> Result<HashMap<String, f64>, String> {
+    let (start, end) = local_date_range(start_date, end_date)?;
+    let heartbeats = Database::new().await?.get_heartbeats_between(start, end).await?;
+    let durations = heartbeat_durations(&heartbeats);
+
+    let mut totals: HashMap<String, f64> = HashMap::new();
+    for (heartbeat, duration) in heartbeats.iter().zip(durations) {
+        if heartbeat.project.as_deref() != Some(project) {
+            continue;
+        }
+        if let Some(name) = key(heartbeat) {
+            *totals.entry(name).or_default() += duration;
+        }
+    }
+    Ok(totals)
+}
+
+async fn load_breakdown(
+    api_config: &ApiConfig,
+    state: &Arc<tauri::async_runtime::Mutex<AuthState>>,
+    project: &str,
+    start_date: &str,
+    end_date: &str,
+    server_key: &str,
+    local_key: fn(&crate::session::HeartbeatData) -> Option<String>,
+) -> Result<(BreakdownSource, HashMap<String, f64>), String> {
+    let server = match access_token(state).await {
+        Ok(token) => {
+            fetch_summary_totals(&resolve_base_url(api_config), &token, Some(project), start_date, end_date, server_key).await
+        }
+        Err(e) => Err(e),
+    };
+
+    match server {
+        Ok(totals) if !totals.is_empty() => return Ok((BreakdownSource::Server, totals)),
+        Ok(_) => push_log("debug", "backend", format!("No server {} for {}, using local heartbeats", server_key, project)),
+        Err(e) => push_log("warn", "backend", format!("Falling back to local {} for {}: {}", server_key, project, e)),
+    }
+
+    let totals = local_breakdown(project, start_date, end_date, local_key).await?;
+    Ok((BreakdownSource::Local, totals))
+}
+
+#[tauri::command]
+pub async fn get_project_files(
+    project: String,
+    range: Option<DateRange>,
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<ProjectFilesResponse, String> {
+    let (start_date, end_date) = range.unwrap_or_default().resolve();
+    let (source, entities) = load_breakdown(
+        &api_config,
+        state.inner(),
+        &project,
+        &start_date,
+        &end_date,
+        "entities",
+        |heartbeat| heartbeat.entity.clone(),
+    )
+    .await?;
+
+    let (root_path, tree) = build_file_tree(&project, &entities);
+    Ok(ProjectFilesResponse {
+        project,
+        start_date,
+        end_date,
+        source,
+        root_path,
+        tree,
+    })
+}
+
+#[tauri::command]
+pub async fn get_project_branches(
+    project: String,
+    range: Option<DateRange>,
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<ProjectBranchesResponse, String> {
+    let (start_date, end_date) = range.unwrap_or_default().resolve();
+    let (source, branches) = load_breakdown(
+        &api_config,
+        state.inner(),
+        &project,
+        &start_date,
+        &end_date,
+        "branches",
+        |heartbeat| Some(heartbeat.branch.clone().unwrap_or_else(|| UNKNOWN_BRANCH.to_string())),
+    )
+    .await?;
+
+    let (total_seconds, branches) = branch_times(branches);
+    Ok(ProjectBranchesResponse {
+        project,
+        start_date,
+        end_date,
+        source,
+        total_seconds,
+        branches,
+    })
+}
@@ -5,28 +5,89 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::Path;
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 use crate::push_log;
+use crate::repositories::{IndexedRepository, RepositoryInfo};
+use crate::session::HeartbeatData;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AuthState {
     pub is_authenticated: bool,
     pub access_token: Option<String>,
     pub user_info: Option<HashMap<String, serde_json::Value>>,
+    pub credential_type: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[allow(dead_code)]
 pub struct SessionRecord {
     pub id: String,
+    pub account_key: String,
     pub is_authenticated: bool,
-    pub access_token: Option<String>,
-    pub user_info: Option<String>, 
+    pub credential_type: String,
+    pub user_info: Option<HashMap<String, serde_json::Value>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub last_accessed_at: DateTime<Utc>,
 }
 
+// One row per account: the server-side user id when /me returned one, otherwise a hash of
+// the token so the raw credential never ends up in an index.
+pub fn account_key_for(
+    user_info: Option<&HashMap<String, serde_json::Value>>,
+    access_token: Option<&str>,
+) -> String {
+    let user_id = user_info
+        .and_then(|info| info.get("id").or_else(|| info.get("username")))
+        .and_then(|value| match value {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        })
+        .filter(|id| !id.is_empty());
+
+    match (user_id, access_token) {
+        (Some(id), _) => format!("user:{}", id),
+        (None, Some(token)) => {
+            let digest = Sha256::digest(token.as_bytes());
+            let hex: String = digest.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+            format!("token:{}", hex)
+        }
+        (None, None) => "anonymous".to_string(),
+    }
+}
+
+fn parse_user_info(json: Option<String>) -> Option<HashMap<String, serde_json::Value>> {
+    json.and_then(|json| serde_json::from_str(&json).ok())
+}
+
+fn parse_timestamp(value: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProjectMetadata {
+    pub project_name: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+impl ProjectMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.alias.is_none() && self.tags.is_empty() && self.color.is_none() && !self.archived && self.notes.is_none()
+    }
+}
+
 pub struct Database {
     pool: SqlitePool,
 }
@@ -142,6 +203,16 @@ impl Database {
         .await
         .map_err(|e| format!("Failed to create sessions table: {}", e))?;
 
+        self.add_column_if_missing("sessions", "credential_type", "TEXT NOT NULL DEFAULT 'oauth'")
+            .await?;
+        self.add_column_if_missing("sessions", "account_key", "TEXT").await?;
+        self.deduplicate_sessions().await?;
+
+        sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS idx_sessions_account_key ON sessions (account_key)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to create sessions account index: {}", e))?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS statistics_cache (
@@ -156,48 +227,334 @@ impl Database {
         .await
         .map_err(|e| format!("Failed to create statistics_cache table: {}", e))?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS heartbeats (
+                id INTEGER PRIMARY KEY,
+                project TEXT,
+                editor TEXT,
+                language TEXT,
+                entity TEXT,
+                category TEXT,
+                machine TEXT,
+                operating_system TEXT,
+                time REAL NOT NULL,
+                recorded_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create heartbeats table: {}", e))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_heartbeats_time ON heartbeats (time)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to create heartbeats index: {}", e))?;
+
+        self.add_column_if_missing("heartbeats", "branch", "TEXT").await?;
+        self.add_column_if_missing("heartbeats", "repository_root", "TEXT").await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS repositories (
+                root TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                project TEXT,
+                remote_url TEXT,
+                web_url TEXT,
+                branch TEXT,
+                last_seen REAL NOT NULL,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create repositories table: {}", e))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS project_metadata (
+                project_name TEXT PRIMARY KEY,
+                alias TEXT,
+                color TEXT,
+                archived INTEGER NOT NULL DEFAULT 0,
+                notes TEXT,
+                updated_at TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create project_metadata table: {}", e))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS project_tags (
+                project_name TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                PRIMARY KEY (project_name, tag)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create project_tags table: {}", e))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS weekly_digests (
+                account_key TEXT NOT NULL,
+                week_start TEXT NOT NULL,
+                data TEXT NOT NULL,
+                generated_at TEXT NOT NULL,
+                PRIMARY KEY (account_key, week_start)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to create weekly_digests table: {}", e))?;
+
         Ok(())
     }
 
-    pub async fn save_session(&self, auth_state: &AuthState) -> Result<String, String> {
-        let session_id = Uuid::new_v4().to_string();
-        let now = Utc::now();
+    async fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<(), String> {
+        let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to read {} columns: {}", table, e))?;
 
-        let user_info_json = match &auth_state.user_info {
-            Some(info) => Some(
-                serde_json::to_string(info)
-                    .map_err(|e| format!("Failed to serialize user info: {}", e))?,
-            ),
-            None => None,
-        };
+        if columns.iter().any(|row| row.get::<String, _>("name") == column) {
+            return Ok(());
+        }
+
+        sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to add {}.{} column: {}", table, column, e))?;
+
+        Ok(())
+    }
+
+    // Older versions inserted a fresh row on every save; give those rows an account key
+    // and keep only the most recently used one per account.
+    async fn deduplicate_sessions(&self) -> Result<(), String> {
+        let rows = sqlx::query("SELECT id, access_token, user_info FROM sessions WHERE account_key IS NULL")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to read sessions for migration: {}", e))?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        for row in &rows {
+            let id: String = row.get("id");
+            let access_token: Option<String> = row.get("access_token");
+            let user_info = parse_user_info(row.get("user_info"));
+            let account_key = account_key_for(user_info.as_ref(), access_token.as_deref());
+
+            sqlx::query("UPDATE sessions SET account_key = ? WHERE id = ?")
+                .bind(&account_key)
+                .bind(&id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| format!("Failed to backfill session account key: {}", e))?;
+        }
 
+        let removed = sqlx::query(
+            r#"
+            DELETE FROM sessions
+            WHERE id NOT IN (
+                SELECT id FROM (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY account_key ORDER BY last_accessed_at DESC
+                    ) AS rank
+                    FROM sessions
+                )
+                WHERE rank = 1
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to deduplicate sessions: {}", e))?
+        .rows_affected();
+
+        push_log("info", "backend", format!(
+            "Migrated {} session rows, removed {} duplicates",
+            rows.len(),
+            removed
+        ));
+        Ok(())
+    }
+
+    pub async fn integrity_check(&self) -> Result<Vec<String>, String> {
+        let rows = sqlx::query("PRAGMA integrity_check")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to run integrity check: {}", e))?;
+
+        Ok(rows.into_iter().map(|row| row.get::<String, _>(0)).collect())
+    }
+
+    pub async fn record_heartbeat(&self, heartbeat: &HeartbeatData) -> Result<(), String> {
         sqlx::query(
             r#"
-            INSERT INTO sessions (id, is_authenticated, access_token, user_info, created_at, updated_at, last_accessed_at)
-            VALUES (?, ?, ?, ?, ?, ?, ?)
+            INSERT OR IGNORE INTO heartbeats (id, project, editor, language, entity, category, machine, operating_system, branch, repository_root, time, recorded_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
-        .bind(&session_id)
-        .bind(auth_state.is_authenticated as i32)
-        .bind(&auth_state.access_token)
-        .bind(&user_info_json)
-        .bind(now.to_rfc3339())
-        .bind(now.to_rfc3339())
-        .bind(now.to_rfc3339())
+        .bind(heartbeat.id as i64)
+        .bind(&heartbeat.project)
+        .bind(&heartbeat.editor)
+        .bind(&heartbeat.language)
+        .bind(&heartbeat.entity)
+        .bind(&heartbeat.category)
+        .bind(&heartbeat.machine)
+        .bind(&heartbeat.operating_system)
+        .bind(&heartbeat.branch)
+        .bind(heartbeat.repository.as_ref().map(|repo| repo.root.as_str()))
+        .bind(heartbeat.timestamp as f64)
+        .bind(Utc::now().to_rfc3339())
         .execute(&self.pool)
         .await
-        .map_err(|e| format!("Failed to save session: {}", e))?;
+        .map_err(|e| format!("Failed to record heartbeat: {}", e))?;
+
+        Ok(())
+    }
 
-        Ok(session_id)
+    pub async fn get_heartbeats_between(&self, start: i64, end: i64) -> Result<Vec<HeartbeatData>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT h.id, h.project, h.editor, h.language, h.entity, h.category, h.machine, h.operating_system,
+                   h.branch, h.time, r.root, r.name, r.remote_url, r.web_url
+            FROM heartbeats h
+            LEFT JOIN repositories r ON r.root = h.repository_root
+            WHERE h.time >= ? AND h.time < ?
+            ORDER BY h.time ASC
+            "#,
+        )
+        .bind(start as f64)
+        .bind(end as f64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to load heartbeats: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let time: f64 = row.get("time");
+                let branch: Option<String> = row.get("branch");
+                let repository = row.get::<Option<String>, _>("root").map(|root| RepositoryInfo {
+                    root,
+                    name: row.get("name"),
+                    remote_url: row.get("remote_url"),
+                    web_url: row.get("web_url"),
+                    branch: branch.clone(),
+                });
+                HeartbeatData {
+                    id: row.get::<i64, _>("id") as u32,
+                    project: row.get("project"),
+                    editor: row.get("editor"),
+                    language: row.get("language"),
+                    entity: row.get("entity"),
+                    time,
+                    timestamp: time as i64,
+                    created_at: None,
+                    category: row.get("category"),
+                    operating_system: row.get("operating_system"),
+                    machine: row.get("machine"),
+                    branch,
+                    repository,
+                }
+            })
+            .collect())
     }
 
-    #[allow(dead_code)]
-    pub async fn update_session(
-        &self,
-        session_id: &str,
-        auth_state: &AuthState,
-    ) -> Result<(), String> {
+    pub async fn upsert_repository(&self, repository: &RepositoryInfo, project: Option<&str>, seen_at: i64) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO repositories (root, name, project, remote_url, web_url, branch, last_seen, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (root) DO UPDATE SET
+                name = excluded.name,
+                project = COALESCE(excluded.project, repositories.project),
+                remote_url = excluded.remote_url,
+                web_url = excluded.web_url,
+                branch = excluded.branch,
+                last_seen = MAX(repositories.last_seen, excluded.last_seen),
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&repository.root)
+        .bind(&repository.name)
+        .bind(project)
+        .bind(&repository.remote_url)
+        .bind(&repository.web_url)
+        .bind(&repository.branch)
+        .bind(seen_at as f64)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to save repository: {}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn list_repositories(&self) -> Result<Vec<IndexedRepository>, String> {
+        let rows = sqlx::query(
+            "SELECT root, name, project, remote_url, web_url, branch, last_seen FROM repositories ORDER BY last_seen DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to load repositories: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| IndexedRepository {
+                repository: RepositoryInfo {
+                    root: row.get("root"),
+                    name: row.get("name"),
+                    remote_url: row.get("remote_url"),
+                    web_url: row.get("web_url"),
+                    branch: row.get("branch"),
+                },
+                project: row.get("project"),
+                last_seen: row.get::<f64, _>("last_seen") as i64,
+            })
+            .collect())
+    }
+
+    pub async fn get_recent_editors(&self, since: i64) -> Result<Vec<(String, i64)>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT editor, MAX(time) AS last_seen
+            FROM heartbeats
+            WHERE editor IS NOT NULL AND time >= ?
+            GROUP BY editor
+            "#,
+        )
+        .bind(since as f64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to load recent editors: {}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let editor: String = row.get("editor");
+                let last_seen: f64 = row.get("last_seen");
+                (editor, last_seen as i64)
+            })
+            .collect())
+    }
+
+    pub async fn save_session(&self, auth_state: &AuthState) -> Result<String, String> {
         let now = Utc::now();
+        let account_key = account_key_for(auth_state.user_info.as_ref(), auth_state.access_token.as_deref());
 
         let user_info_json = match &auth_state.user_info {
             Some(info) => Some(
@@ -207,30 +564,129 @@ impl Database {
             None => None,
         };
 
-        sqlx::query(
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        // A login that finished before /me answered was stored under the token hash; once the
+        // profile arrives that row and its digests move to the user key instead of lingering
+        // as a second account.
+        if let (true, Some(token)) = (account_key.starts_with("user:"), auth_state.access_token.as_deref()) {
+            let token_key = account_key_for(None, Some(token));
+
+            for table in ["sessions", "weekly_digests"] {
+                sqlx::query(&format!("UPDATE OR IGNORE {} SET account_key = ? WHERE account_key = ?", table))
+                    .bind(&account_key)
+                    .bind(&token_key)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to re-key {}: {}", table, e))?;
+
+                sqlx::query(&format!("DELETE FROM {} WHERE account_key = ?", table))
+                    .bind(&token_key)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to remove re-keyed {}: {}", table, e))?;
+            }
+        }
+
+        let row = sqlx::query(
             r#"
-            UPDATE sessions 
-            SET is_authenticated = ?, access_token = ?, user_info = ?, updated_at = ?, last_accessed_at = ?
-            WHERE id = ?
+            INSERT INTO sessions (id, account_key, is_authenticated, access_token, user_info, credential_type, created_at, updated_at, last_accessed_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT (account_key) DO UPDATE SET
+                is_authenticated = excluded.is_authenticated,
+                access_token = excluded.access_token,
+                user_info = excluded.user_info,
+                credential_type = excluded.credential_type,
+                updated_at = excluded.updated_at,
+                last_accessed_at = excluded.last_accessed_at
+            RETURNING id
             "#,
         )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&account_key)
         .bind(auth_state.is_authenticated as i32)
         .bind(&auth_state.access_token)
         .bind(&user_info_json)
+        .bind(&auth_state.credential_type)
         .bind(now.to_rfc3339())
         .bind(now.to_rfc3339())
-        .bind(session_id)
-        .execute(&self.pool)
+        .bind(now.to_rfc3339())
+        .fetch_one(&mut *tx)
         .await
-        .map_err(|e| format!("Failed to update session: {}", e))?;
+        .map_err(|e| format!("Failed to save session: {}", e))?;
 
-        Ok(())
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit session: {}", e))?;
+
+        Ok(row.get("id"))
+    }
+
+    pub async fn list_sessions(&self) -> Result<Vec<SessionRecord>, String> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, account_key, is_authenticated, credential_type, user_info, created_at, updated_at, last_accessed_at
+            FROM sessions
+            ORDER BY last_accessed_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to list sessions: {}", e))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SessionRecord {
+                id: row.get("id"),
+                account_key: row.get("account_key"),
+                is_authenticated: row.get::<i32, _>("is_authenticated") != 0,
+                credential_type: row.get("credential_type"),
+                user_info: parse_user_info(row.get("user_info")),
+                created_at: parse_timestamp(&row.get::<String, _>("created_at")),
+                updated_at: parse_timestamp(&row.get::<String, _>("updated_at")),
+                last_accessed_at: parse_timestamp(&row.get::<String, _>("last_accessed_at")),
+            })
+            .collect())
+    }
+
+    pub async fn get_session_credentials(&self, session_id: &str) -> Result<Option<(String, Option<String>, String)>, String> {
+        let row = sqlx::query("SELECT account_key, access_token, credential_type FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load session: {}", e))?;
+
+        Ok(row.map(|row| (row.get("account_key"), row.get("access_token"), row.get("credential_type"))))
+    }
+
+    pub async fn delete_session(&self, session_id: &str) -> Result<bool, String> {
+        let result = sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to delete session: {}", e))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete_session_for_account(&self, account_key: &str) -> Result<bool, String> {
+        let result = sqlx::query("DELETE FROM sessions WHERE account_key = ?")
+            .bind(account_key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to delete session: {}", e))?;
+
+        Ok(result.rows_affected() > 0)
     }
 
     pub async fn load_latest_session(&self) -> Result<Option<AuthState>, String> {
         let row = sqlx::query(
             r#"
-            SELECT id, is_authenticated, access_token, user_info, last_accessed_at
+            SELECT id, is_authenticated, access_token, user_info, credential_type, last_accessed_at
             FROM sessions 
             ORDER BY last_accessed_at DESC 
             LIMIT 1
@@ -246,16 +702,9 @@ impl Database {
                 let is_authenticated: i32 = row.get("is_authenticated");
                 let access_token: Option<String> = row.get("access_token");
                 let user_info_json: Option<String> = row.get("user_info");
+                let credential_type: String = row.get("credential_type");
 
-                let user_info = match user_info_json {
-                    Some(json) => {
-                        match serde_json::from_str::<HashMap<String, serde_json::Value>>(&json) {
-                            Ok(info) => Some(info),
-                            Err(_) => None, 
-                        }
-                    }
-                    None => None,
-                };
+                let user_info = parse_user_info(user_info_json);
 
                 
                 self.update_last_accessed(&session_id).await?;
@@ -264,6 +713,7 @@ impl Database {
                     is_authenticated: is_authenticated != 0,
                     access_token,
                     user_info,
+                    credential_type,
                 }))
             }
             None => Ok(None),
@@ -292,16 +742,16 @@ impl Database {
         Ok(())
     }
 
-    pub async fn cleanup_old_sessions(&self, days_old: i64) -> Result<(), String> {
+    pub async fn cleanup_old_sessions(&self, days_old: i64) -> Result<u64, String> {
         let cutoff = Utc::now() - chrono::Duration::days(days_old);
 
-        sqlx::query("DELETE FROM sessions WHERE last_accessed_at < ?")
+        let result = sqlx::query("DELETE FROM sessions WHERE last_accessed_at < ?")
             .bind(cutoff.to_rfc3339())
             .execute(&self.pool)
             .await
             .map_err(|e| format!("Failed to cleanup old sessions: {}", e))?;
 
-        Ok(())
+        Ok(result.rows_affected())
     }
 
     pub async fn get_cached_data(&self, cache_key: &str) -> Result<Option<String>, String> {
@@ -362,6 +812,162 @@ impl Database {
         Ok(())
     }
 
+    pub async fn get_all_project_metadata(&self) -> Result<HashMap<String, ProjectMetadata>, String> {
+        let rows = sqlx::query("SELECT project_name, alias, color, archived, notes FROM project_metadata")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load project metadata: {}", e))?;
+
+        let mut metadata: HashMap<String, ProjectMetadata> = rows
+            .into_iter()
+            .map(|row| {
+                let project_name: String = row.get("project_name");
+                (project_name.clone(), ProjectMetadata {
+                    project_name,
+                    alias: row.get("alias"),
+                    tags: Vec::new(),
+                    color: row.get("color"),
+                    archived: row.get::<i32, _>("archived") != 0,
+                    notes: row.get("notes"),
+                })
+            })
+            .collect();
+
+        let tag_rows = sqlx::query("SELECT project_name, tag FROM project_tags ORDER BY tag")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load project tags: {}", e))?;
+
+        for row in tag_rows {
+            let project_name: String = row.get("project_name");
+            metadata
+                .entry(project_name.clone())
+                .or_insert_with(|| ProjectMetadata {
+                    project_name,
+                    ..Default::default()
+                })
+                .tags
+                .push(row.get("tag"));
+        }
+
+        Ok(metadata)
+    }
+
+    pub async fn save_project_metadata(&self, metadata: &ProjectMetadata) -> Result<(), String> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| format!("Failed to start transaction: {}", e))?;
+
+        sqlx::query("DELETE FROM project_tags WHERE project_name = ?")
+            .bind(&metadata.project_name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to clear project tags: {}", e))?;
+
+        if metadata.is_empty() {
+            sqlx::query("DELETE FROM project_metadata WHERE project_name = ?")
+                .bind(&metadata.project_name)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to delete project metadata: {}", e))?;
+        } else {
+            sqlx::query(
+                r#"
+                INSERT INTO project_metadata (project_name, alias, color, archived, notes, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT (project_name) DO UPDATE SET
+                    alias = excluded.alias,
+                    color = excluded.color,
+                    archived = excluded.archived,
+                    notes = excluded.notes,
+                    updated_at = excluded.updated_at
+                "#,
+            )
+            .bind(&metadata.project_name)
+            .bind(&metadata.alias)
+            .bind(&metadata.color)
+            .bind(metadata.archived as i32)
+            .bind(&metadata.notes)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to save project metadata: {}", e))?;
+
+            for tag in &metadata.tags {
+                sqlx::query("INSERT OR IGNORE INTO project_tags (project_name, tag) VALUES (?, ?)")
+                    .bind(&metadata.project_name)
+                    .bind(tag)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| format!("Failed to save project tag: {}", e))?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| format!("Failed to commit project metadata: {}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn save_weekly_digest(&self, account_key: &str, week_start: &str, data: &str) -> Result<(), String> {
+        sqlx::query(
+            r#"
+            INSERT INTO weekly_digests (account_key, week_start, data, generated_at)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT (account_key, week_start) DO UPDATE SET
+                data = excluded.data,
+                generated_at = excluded.generated_at
+            "#,
+        )
+        .bind(account_key)
+        .bind(week_start)
+        .bind(data)
+        .bind(Utc::now().to_rfc3339())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| format!("Failed to save weekly digest: {}", e))?;
+
+        Ok(())
+    }
+
+    pub async fn get_weekly_digest(&self, account_key: &str, week_start: &str) -> Result<Option<String>, String> {
+        let row = sqlx::query("SELECT data FROM weekly_digests WHERE account_key = ? AND week_start = ?")
+            .bind(account_key)
+            .bind(week_start)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load weekly digest: {}", e))?;
+
+        Ok(row.map(|row| row.get("data")))
+    }
+
+    pub async fn list_weekly_digests(&self, account_key: &str) -> Result<Vec<String>, String> {
+        let rows = sqlx::query("SELECT data FROM weekly_digests WHERE account_key = ? ORDER BY week_start DESC")
+            .bind(account_key)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load weekly digests: {}", e))?;
+
+        Ok(rows.into_iter().map(|row| row.get("data")).collect())
+    }
+
+    pub async fn clear_heartbeats(&self) -> Result<(), String> {
+        sqlx::query("DELETE FROM heartbeats")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to clear heartbeats: {}", e))?;
+
+        sqlx::query("DELETE FROM repositories")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to clear repositories: {}", e))?;
+
+        Ok(())
+    }
+
     pub async fn clear_all_cache(&self) -> Result<(), String> {
         sqlx::query("DELETE FROM statistics_cache")
             .execute(&self.pool)
@@ -475,3 +1081,76 @@ pub fn get_platform_info() -> Result<serde_json::Value, String> {
 
     Ok(platform_info)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::isolated;
+
+    fn state(token: &str, user_id: Option<&str>) -> AuthState {
+        AuthState {
+            is_authenticated: true,
+            access_token: Some(token.to_string()),
+            user_info: Some(
+                user_id
+                    .map(|id| HashMap::from([("id".to_string(), serde_json::json!(id))]))
+                    .unwrap_or_default(),
+            ),
+            credential_type: "oauth".to_string(),
+        }
+    }
+
+    #[test]
+    fn profile_arrival_rekeys_the_token_session_and_its_digests() {
+        let _guard = isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let db = Database::new().await.unwrap();
+            let token = "rekey-test-token";
+            let token_key = account_key_for(None, Some(token));
+
+            let first_id = db.save_session(&state(token, None)).await.unwrap();
+            db.save_weekly_digest(&token_key, "2026-01-05", "{}").await.unwrap();
+
+            let second_id = db.save_session(&state(token, Some("rekey-user"))).await.unwrap();
+            assert_eq!(first_id, second_id);
+
+            let keys: Vec<String> = db
+                .list_sessions()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|session| session.account_key)
+                .filter(|key| key == &token_key || key == "user:rekey-user")
+                .collect();
+            assert_eq!(keys, vec!["user:rekey-user".to_string()]);
+            assert!(db.list_weekly_digests(&token_key).await.unwrap().is_empty());
+            assert_eq!(db.list_weekly_digests("user:rekey-user").await.unwrap().len(), 1);
+
+            db.delete_session(&second_id).await.unwrap();
+        });
+    }
+
+    #[test]
+    fn deleting_one_account_keeps_the_others() {
+        let _guard = isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let db = Database::new().await.unwrap();
+            db.save_session(&state("first-account-token", Some("first-account"))).await.unwrap();
+            let other_id = db.save_session(&state("second-account-token", Some("second-account"))).await.unwrap();
+
+            assert!(db.delete_session_for_account("user:first-account").await.unwrap());
+
+            let keys: Vec<String> = db
+                .list_sessions()
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|session| session.account_key)
+                .collect();
+            assert!(!keys.contains(&"user:first-account".to_string()));
+            assert!(keys.contains(&"user:second-account".to_string()));
+
+            db.delete_session(&other_id).await.unwrap();
+        });
+    }
+}
@@ -1,6 +1,55 @@
-use crate::database::{get_hackatime_config_dir, get_hackatime_data_dir, get_hackatime_logs_dir, get_platform_info, Database};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::auth::AuthState;
+use crate::auth_flow::{AuthFlow, AuthFlowState};
+use crate::database::{account_key_for, get_hackatime_config_dir, get_hackatime_data_dir, get_hackatime_logs_dir, get_platform_info, Database, SessionRecord};
 use crate::push_log;
 
+const SESSION_RETENTION_DAYS: i64 = 90;
+const SESSION_CLEANUP_INTERVAL_SECS: u64 = 6 * 60 * 60;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SessionInfo {
+    #[serde(flatten)]
+    pub record: SessionRecord,
+    pub is_current: bool,
+}
+
+async fn current_account_key(auth_state: &Arc<tauri::async_runtime::Mutex<AuthState>>) -> Option<String> {
+    let auth_state = auth_state.lock().await;
+    if !auth_state.is_authenticated {
+        return None;
+    }
+    Some(account_key_for(auth_state.user_info.as_ref(), auth_state.access_token.as_deref()))
+}
+
+pub fn start_session_cleanup() {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(SESSION_CLEANUP_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+
+            let db = match Database::new().await {
+                Ok(db) => db,
+                Err(e) => {
+                    push_log("warn", "backend", format!("Session cleanup skipped: {}", e));
+                    continue;
+                }
+            };
+            match db.cleanup_old_sessions(SESSION_RETENTION_DAYS).await {
+                Ok(0) => {}
+                Ok(removed) => push_log("info", "backend", format!("Removed {} sessions unused for {} days", removed, SESSION_RETENTION_DAYS)),
+                Err(e) => push_log("warn", "backend", format!("Session cleanup failed: {}", e)),
+            }
+            if let Err(e) = db.cleanup_expired_cache().await {
+                push_log("warn", "backend", format!("Cache cleanup failed: {}", e));
+            }
+        }
+    });
+}
+
 #[tauri::command]
 pub async fn get_hackatime_directories() -> Result<serde_json::Value, String> {
     let config_dir = get_hackatime_config_dir()?;
@@ -15,9 +64,105 @@ pub async fn get_hackatime_directories() -> Result<serde_json::Value, String> {
 }
 
 #[tauri::command]
-pub async fn cleanup_old_sessions(days_old: i64) -> Result<(), String> {
+pub async fn cleanup_old_sessions(days_old: i64) -> Result<u64, String> {
+    let db = Database::new().await?;
+    db.cleanup_old_sessions(days_old).await
+}
+
+#[tauri::command]
+pub async fn list_sessions(
+    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<Vec<SessionInfo>, String> {
+    let current = current_account_key(auth_state.inner()).await;
+    let db = Database::new().await?;
+
+    Ok(db
+        .list_sessions()
+        .await?
+        .into_iter()
+        .map(|record| SessionInfo {
+            is_current: current.as_deref() == Some(record.account_key.as_str()),
+            record,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn inspect_session(
+    session_id: String,
+    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<SessionInfo, String> {
+    list_sessions(auth_state)
+        .await?
+        .into_iter()
+        .find(|session| session.record.id == session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
+#[tauri::command]
+pub async fn revoke_session(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<(), String> {
+    let db = Database::new().await?;
+    let (account_key, access_token, credential_type) = db
+        .get_session_credentials(&session_id)
+        .await?
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+    let flow = AuthFlow::from_app(&app_handle);
+    if let (Some(token), "oauth") = (&access_token, credential_type.as_str()) {
+        if let Err(e) = flow.revoke(token).await {
+            push_log("warn", "backend", format!("Failed to revoke token for session {}: {}", session_id, e));
+        }
+    }
+    db.delete_session(&session_id).await?;
+
+    if current_account_key(auth_state.inner()).await.as_deref() == Some(account_key.as_str()) {
+        {
+            let mut auth_state = auth_state.lock().await;
+            auth_state.is_authenticated = false;
+            auth_state.access_token = None;
+            auth_state.user_info = None;
+        }
+        flow.transition(AuthFlowState::Idle).await;
+        crate::tray::refresh_tray_status(&app_handle).await;
+    }
+
+    push_log("info", "backend", format!("Revoked session {}", session_id));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_all_accounts(
+    app_handle: tauri::AppHandle,
+    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<(), String> {
     let db = Database::new().await?;
-    db.cleanup_old_sessions(days_old).await?;
+    let flow = AuthFlow::from_app(&app_handle);
+
+    for session in db.list_sessions().await? {
+        if let Some((_, Some(token), credential_type)) = db.get_session_credentials(&session.id).await? {
+            if credential_type == "oauth" {
+                if let Err(e) = flow.revoke(&token).await {
+                    push_log("warn", "backend", format!("Failed to revoke token for session {}: {}", session.id, e));
+                }
+            }
+        }
+    }
+    db.clear_sessions().await?;
+
+    {
+        let mut auth_state = auth_state.lock().await;
+        auth_state.is_authenticated = false;
+        auth_state.access_token = None;
+        auth_state.user_info = None;
+    }
+    flow.transition(AuthFlowState::Idle).await;
+    crate::tray::refresh_tray_status(&app_handle).await;
+
+    push_log("info", "backend", "Removed all saved accounts".to_string());
     Ok(())
 }
 
@@ -0,0 +1,149 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{Emitter, Manager, Url};
+
+use crate::auth_flow::AuthFlow;
+use crate::push_log;
+
+pub const DEEP_LINK_SCHEME: &str = "hackatime";
+
+static DEEP_LINKS_REGISTERED: AtomicBool = AtomicBool::new(true);
+
+pub fn mark_registration_failed() {
+    DEEP_LINKS_REGISTERED.store(false, Ordering::Relaxed);
+}
+
+pub fn deep_links_registered() -> bool {
+    DEEP_LINKS_REGISTERED.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkRoute {
+    AuthCallback {
+        code: Option<String>,
+        state: Option<String>,
+        error: Option<String>,
+    },
+    Project {
+        name: String,
+    },
+    Stats {
+        range: Option<String>,
+    },
+    Setup {
+        api_key: String,
+        api_url: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "route", rename_all = "snake_case")]
+pub enum DeepLinkNavigation {
+    Project { name: String },
+    Stats { range: Option<String> },
+    Setup { api_key: String, api_url: Option<String> },
+}
+
+fn query_param(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.into_owned())
+        .filter(|v| !v.is_empty())
+}
+
+pub fn parse_deep_link(raw: &str) -> Result<DeepLinkRoute, String> {
+    let url = Url::parse(raw.trim()).map_err(|e| format!("Invalid deep link '{}': {}", raw, e))?;
+
+    if url.scheme() != DEEP_LINK_SCHEME {
+        return Err(format!("Unsupported deep link scheme '{}'", url.scheme()));
+    }
+
+    let host = url.host_str().unwrap_or_default();
+    let segments: Vec<String> = url
+        .path_segments()
+        .map(|segments| {
+            segments
+                .filter(|s| !s.is_empty())
+                .map(|s| urlencoding::decode(s).map(|d| d.into_owned()).unwrap_or_else(|_| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match (host, segments.as_slice()) {
+        ("auth", [callback]) if callback == "callback" => Ok(DeepLinkRoute::AuthCallback {
+            code: query_param(&url, "code"),
+            state: query_param(&url, "state"),
+            error: query_param(&url, "error_description").or_else(|| query_param(&url, "error")),
+        }),
+        ("project", [_, ..]) => Ok(DeepLinkRoute::Project {
+            name: segments.join("/"),
+        }),
+        ("stats", []) => Ok(DeepLinkRoute::Stats {
+            range: query_param(&url, "range"),
+        }),
+        ("setup", []) => {
+            let api_key = query_param(&url, "api_key").ok_or("Setup deep link is missing api_key")?;
+            Ok(DeepLinkRoute::Setup {
+                api_key,
+                api_url: query_param(&url, "api_url"),
+            })
+        }
+        _ => Err(format!("Unknown deep link route '{}{}'", host, url.path())),
+    }
+}
+
+pub fn handle_deep_link(app: &tauri::AppHandle, raw: &str) {
+    let route = match parse_deep_link(raw) {
+        Ok(route) => route,
+        Err(e) => {
+            push_log("error", "backend", format!("Ignoring deep link: {}", e));
+            return;
+        }
+    };
+
+    match route {
+        DeepLinkRoute::AuthCallback { code, state, error } => handle_auth_callback(app, code, state, error),
+        DeepLinkRoute::Project { name } => {
+            push_log("info", "backend", format!("Opening project '{}' from deep link", name));
+            navigate(app, DeepLinkNavigation::Project { name });
+        }
+        DeepLinkRoute::Stats { range } => {
+            push_log("info", "backend", format!("Opening statistics from deep link (range: {:?})", range));
+            navigate(app, DeepLinkNavigation::Stats { range });
+        }
+        DeepLinkRoute::Setup { api_key, api_url } => {
+            crate::redaction::register_secret(&api_key);
+            // Never write the key from here: any web page can open a deep link, so the
+            // frontend asks the user to confirm before it calls the setup commands.
+            push_log("info", "backend", "Received setup deep link, waiting for confirmation".to_string());
+            navigate(app, DeepLinkNavigation::Setup { api_key, api_url });
+        }
+    }
+}
+
+fn navigate(app: &tauri::AppHandle, navigation: DeepLinkNavigation) {
+    if let Some(window) = app.get_webview_window("main") {
+        #[cfg(target_os = "macos")]
+        let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+
+    if let Err(e) = app.emit("deep-link-navigate", &navigation) {
+        push_log("error", "backend", format!("Failed to emit deep link navigation: {}", e));
+    }
+}
+
+fn handle_auth_callback(
+    app: &tauri::AppHandle,
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+) {
+    let flow = AuthFlow::from_app(app);
+
+    tauri::async_runtime::spawn(async move {
+        let _ = flow.handle_callback(code, state, error).await;
+    });
+}
@@ -0,0 +1,409 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::auth::{fetch_api_key, AuthState, CredentialType};
+use crate::config::ApiConfig;
+use crate::database::{get_hackatime_config_dir, Database};
+use crate::ini::IniDocument;
+use crate::push_log;
+use crate::setup::get_wakatime_config_path;
+
+const SLOW_API_LATENCY_MS: u128 = 2000;
+const STALE_HEARTBEAT_SECS: i64 = 24 * 3600;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiagnosticCheck {
+    pub id: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+    pub remediation: Option<String>,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiagnosticsReport {
+    pub generated_at: String,
+    pub app_version: String,
+    pub os: String,
+    pub api_base_url: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
+
+struct CheckOutcome {
+    status: CheckStatus,
+    message: String,
+    remediation: Option<String>,
+}
+
+impl CheckOutcome {
+    fn pass(message: impl Into<String>) -> Self {
+        Self { status: CheckStatus::Pass, message: message.into(), remediation: None }
+    }
+
+    fn warn(message: impl Into<String>, remediation: &str) -> Self {
+        Self { status: CheckStatus::Warn, message: message.into(), remediation: Some(remediation.to_string()) }
+    }
+
+    fn fail(message: impl Into<String>, remediation: &str) -> Self {
+        Self { status: CheckStatus::Fail, message: message.into(), remediation: Some(remediation.to_string()) }
+    }
+
+    fn skipped(message: impl Into<String>) -> Self {
+        Self { status: CheckStatus::Skipped, message: message.into(), remediation: None }
+    }
+}
+
+fn record(checks: &mut Vec<DiagnosticCheck>, id: &str, name: &str, started: Instant, outcome: CheckOutcome) {
+    push_log("info", "backend", format!("Diagnostic '{}': {:?} - {}", id, outcome.status, outcome.message));
+    checks.push(DiagnosticCheck {
+        id: id.to_string(),
+        name: name.to_string(),
+        status: outcome.status,
+        message: outcome.message,
+        remediation: outcome.remediation,
+        duration_ms: started.elapsed().as_millis() as u64,
+    });
+}
+
+fn mask_secret(secret: &str) -> String {
+    let visible: String = secret.chars().rev().take(4).collect::<Vec<_>>().into_iter().rev().collect();
+    format!("…{}", visible)
+}
+
+fn check_config_dir() -> CheckOutcome {
+    let config_dir = match get_hackatime_config_dir() {
+        Ok(dir) => dir,
+        Err(e) => return CheckOutcome::fail(e, "Make sure your home directory exists and is accessible."),
+    };
+
+    let test_file = config_dir.join(".diagnostics_write_test");
+    match fs::write(&test_file, "test") {
+        Ok(()) => {
+            let _ = fs::remove_file(&test_file);
+            CheckOutcome::pass(format!("{} is writable", config_dir.display()))
+        }
+        Err(e) => CheckOutcome::fail(
+            format!("Cannot write to {}: {}", config_dir.display(), e),
+            "Check the permissions of the Hackatime data directory or free up disk space.",
+        ),
+    }
+}
+
+fn check_log_file() -> CheckOutcome {
+    match crate::logging::last_log_file_error() {
+        None => CheckOutcome::pass("Log file is being written"),
+        Some(error) => {
+            let at = chrono::DateTime::from_timestamp_millis(error.ts)
+                .map(|ts| ts.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_default();
+            CheckOutcome::warn(
+                format!("{} (at {})", error.message, at),
+                "Check the permissions of the Hackatime logs directory or free up disk space.",
+            )
+        }
+    }
+}
+
+async fn check_database() -> CheckOutcome {
+    let db = match Database::new().await {
+        Ok(db) => db,
+        Err(e) => return CheckOutcome::fail(e, "Quit the app and move sessions.db out of the data directory to recreate it."),
+    };
+
+    match db.integrity_check().await {
+        Ok(results) if results.len() == 1 && results[0] == "ok" => CheckOutcome::pass("PRAGMA integrity_check returned ok"),
+        Ok(results) => CheckOutcome::fail(
+            format!("Integrity check reported problems: {}", results.join("; ")),
+            "Quit the app and move sessions.db out of the data directory to recreate it. You will need to log in again.",
+        ),
+        Err(e) => CheckOutcome::fail(e, "Quit the app and move sessions.db out of the data directory to recreate it."),
+    }
+}
+
+fn check_wakatime_config_file() -> (CheckOutcome, Option<String>) {
+    let config_path = match get_wakatime_config_path() {
+        Ok(path) => path,
+        Err(e) => return (CheckOutcome::fail(e, "Make sure HOME (or USERPROFILE on Windows) is set."), None),
+    };
+
+    if !Path::new(&config_path).exists() {
+        return (
+            CheckOutcome::fail(format!("{} does not exist", config_path), "Run the setup step in the app to create the config."),
+            None,
+        );
+    }
+
+    let content = match fs::read_to_string(&config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            return (
+                CheckOutcome::fail(format!("Failed to read {}: {}", config_path, e), "Check the permissions of the config file."),
+                None,
+            )
+        }
+    };
+
+    let document = IniDocument::parse(&content);
+    let api_key = document.get("settings", "api_key").filter(|v| !v.is_empty());
+    let api_url = document.get("settings", "api_url").filter(|v| !v.is_empty());
+    if let Some(key) = &api_key {
+        crate::redaction::register_secret(key);
+    }
+
+    let outcome = match (&api_key, &api_url) {
+        (Some(_), Some(url)) if url.contains("hackatime") => CheckOutcome::pass(format!("{} has api_key and api_url", config_path)),
+        (Some(_), Some(url)) => CheckOutcome::warn(
+            format!("api_url points to {}", url),
+            "Run the setup step again if you want heartbeats to go to Hackatime.",
+        ),
+        _ => CheckOutcome::fail(
+            format!("{} is missing api_key or api_url in [settings]", config_path),
+            "Run the setup step in the app to add the missing keys.",
+        ),
+    };
+
+    (outcome, api_key)
+}
+
+#[tauri::command]
+pub async fn run_diagnostics(
+    app: tauri::AppHandle,
+    api_config: ApiConfig,
+    auth_state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<DiagnosticsReport, String> {
+    push_log("info", "backend", "Running diagnostics".to_string());
+
+    let base_url = if api_config.base_url.is_empty() {
+        "https://hackatime.hackclub.com".to_string()
+    } else {
+        api_config.base_url.clone()
+    };
+    let (access_token, credential_type) = {
+        let auth_state = auth_state.lock().await;
+        let access_token = if auth_state.is_authenticated {
+            auth_state.access_token.clone()
+        } else {
+            None
+        };
+        (access_token, auth_state.credential_type)
+    };
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut checks = Vec::new();
+
+    let started = Instant::now();
+    record(&mut checks, "config_dir", "Config directory is writable", started, check_config_dir());
+
+    let started = Instant::now();
+    record(&mut checks, "log_file", "Log file is writable", started, check_log_file());
+
+    let started = Instant::now();
+    let outcome = check_database().await;
+    record(&mut checks, "database", "Database integrity", started, outcome);
+
+    let started = Instant::now();
+    let (outcome, config_api_key) = check_wakatime_config_file();
+    record(&mut checks, "wakatime_config", "WakaTime config file", started, outcome);
+
+    let started = Instant::now();
+    let api_reachable = match client.get(&base_url).send().await {
+        Ok(response) => {
+            let latency = started.elapsed().as_millis();
+            let outcome = if response.status().is_server_error() {
+                CheckOutcome::fail(
+                    format!("{} responded with {}", base_url, response.status()),
+                    "Hackatime may be having an outage. Try again later.",
+                )
+            } else if latency > SLOW_API_LATENCY_MS {
+                CheckOutcome::warn(
+                    format!("{} responded in {} ms", base_url, latency),
+                    "Your connection to Hackatime is slow. Check your network or proxy settings.",
+                )
+            } else {
+                CheckOutcome::pass(format!("{} responded in {} ms", base_url, latency))
+            };
+            let reachable = outcome.status != CheckStatus::Fail;
+            record(&mut checks, "api_reachability", "API reachability and latency", started, outcome);
+            reachable
+        }
+        Err(e) => {
+            record(&mut checks, "api_reachability", "API reachability and latency", started, CheckOutcome::fail(
+                format!("Could not reach {}: {}", base_url, e),
+                "Check your internet connection, firewall, and the proxy setting in ~/.wakatime.cfg.",
+            ));
+            false
+        }
+    };
+
+    let started = Instant::now();
+    let token_valid = match (&access_token, api_reachable) {
+        (None, _) => {
+            record(&mut checks, "token", "Access token is valid", started, CheckOutcome::skipped("Not logged in"));
+            false
+        }
+        (Some(_), false) => {
+            record(&mut checks, "token", "Access token is valid", started, CheckOutcome::skipped("API is not reachable"));
+            false
+        }
+        (Some(token), true) => {
+            let outcome = match client
+                .get(format!("{}/api/v1/authenticated/me", base_url))
+                .bearer_auth(token)
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => CheckOutcome::pass("/api/v1/authenticated/me accepted the token"),
+                Ok(response) if response.status().as_u16() == 401 => CheckOutcome::fail(
+                    "The saved access token was rejected",
+                    "Log out and log in again.",
+                ),
+                Ok(response) => CheckOutcome::warn(
+                    format!("/api/v1/authenticated/me responded with {}", response.status()),
+                    "Try again later. If it persists, log out and log in again.",
+                ),
+                Err(e) => CheckOutcome::fail(format!("Request failed: {}", e), "Check your internet connection."),
+            };
+            let valid = outcome.status == CheckStatus::Pass;
+            record(&mut checks, "token", "Access token is valid", started, outcome);
+            valid
+        }
+    };
+
+    let started = Instant::now();
+    let outcome = match (&access_token, token_valid, &config_api_key) {
+        (Some(token), true, Some(config_key)) => {
+            // An API key login stores the key itself, so there is nothing to look up.
+            let account_key = match credential_type {
+                CredentialType::ApiKey => Ok(token.clone()),
+                CredentialType::OAuth => fetch_api_key(&client, &base_url, token).await,
+            };
+            match account_key {
+                Ok(account_key) if &account_key == config_key => {
+                    CheckOutcome::pass(format!("Config uses your API key ({})", mask_secret(config_key)))
+                }
+                Ok(account_key) => CheckOutcome::fail(
+                    format!("Config has {} but your account key is {}", mask_secret(config_key), mask_secret(&account_key)),
+                    "Run the setup step again to write your current API key to ~/.wakatime.cfg.",
+                ),
+                Err(e) => CheckOutcome::warn(format!("Could not fetch your API key: {}", e), "Try again later."),
+            }
+        }
+        (_, true, None) => CheckOutcome::skipped("No api_key in the WakaTime config"),
+        _ => CheckOutcome::skipped("Requires a valid login"),
+    };
+    record(&mut checks, "api_key_match", "Config API key matches account", started, outcome);
+
+    let started = Instant::now();
+    let outcome = match (&access_token, token_valid) {
+        (Some(token), true) => match client
+            .get(format!("{}/api/v1/authenticated/heartbeats/latest", base_url))
+            .bearer_auth(token)
+            .send()
+            .await
+        {
+            Ok(response) if response.status().is_success() => {
+                let body: serde_json::Value = response.json().await.unwrap_or(serde_json::Value::Null);
+                let time = body["time"].as_f64().or_else(|| body["timestamp"].as_f64());
+                match time {
+                    Some(time) => {
+                        let age = chrono::Utc::now().timestamp() - time as i64;
+                        if age > STALE_HEARTBEAT_SECS {
+                            CheckOutcome::warn(
+                                format!("Latest heartbeat is {} hours old", age / 3600),
+                                "Open your editor and make an edit. If nothing arrives, check the editor plugin report.",
+                            )
+                        } else {
+                            CheckOutcome::pass(format!("Latest heartbeat is {} minutes old", age / 60))
+                        }
+                    }
+                    None => CheckOutcome::fail(
+                        "No heartbeats have been received yet",
+                        "Install a WakaTime plugin for your editor and run the setup step.",
+                    ),
+                }
+            }
+            Ok(response) => CheckOutcome::warn(format!("Heartbeat request responded with {}", response.status()), "Try again later."),
+            Err(e) => CheckOutcome::fail(format!("Request failed: {}", e), "Check your internet connection."),
+        },
+        _ => CheckOutcome::skipped("Requires a valid login"),
+    };
+    record(&mut checks, "latest_heartbeat", "Latest heartbeat age", started, outcome);
+
+    Ok(DiagnosticsReport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        app_version: app.package_info().version.to_string(),
+        os: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+        api_base_url: base_url,
+        checks,
+    })
+}
+
+fn render_markdown(report: &DiagnosticsReport) -> String {
+    let mut output = String::new();
+    output.push_str("# Hackatime Desktop diagnostics\n\n");
+    output.push_str(&format!("- Generated: {}\n", report.generated_at));
+    output.push_str(&format!("- App version: {}\n", report.app_version));
+    output.push_str(&format!("- OS: {}\n", report.os));
+    output.push_str(&format!("- API: {}\n\n", report.api_base_url));
+    output.push_str("| Check | Status | Details | Time |\n|---|---|---|---|\n");
+
+    for check in &report.checks {
+        let status = match check.status {
+            CheckStatus::Pass => "PASS",
+            CheckStatus::Warn => "WARN",
+            CheckStatus::Fail => "FAIL",
+            CheckStatus::Skipped => "SKIPPED",
+        };
+        output.push_str(&format!(
+            "| {} | {} | {} | {} ms |\n",
+            check.name,
+            status,
+            check.message.replace('|', "\\|"),
+            check.duration_ms
+        ));
+    }
+
+    let hints: Vec<&DiagnosticCheck> = report.checks.iter().filter(|c| c.remediation.is_some()).collect();
+    if !hints.is_empty() {
+        output.push_str("\n## Suggested fixes\n\n");
+        for check in hints {
+            output.push_str(&format!("- **{}**: {}\n", check.name, check.remediation.as_deref().unwrap_or("")));
+        }
+    }
+
+    output
+}
+
+#[tauri::command]
+pub fn export_diagnostics_report(report: DiagnosticsReport, path: String, format: String) -> Result<String, String> {
+    let content = match format.as_str() {
+        "json" => serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("Failed to serialize diagnostics report: {}", e))?,
+        "markdown" | "md" => render_markdown(&report),
+        other => return Err(format!("Unsupported report format: {}", other)),
+    };
+
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to write diagnostics report: {}", e))?;
+
+    push_log("info", "backend", format!("Diagnostics report exported to {}", path));
+    Ok(path)
+}
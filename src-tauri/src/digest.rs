@@ -0,0 +1,599 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::activity::{local_date_range, local_seconds_by};
+use crate::auth::AuthState;
+use crate::breakdown::{fetch_summaries, summary_totals};
+use crate::config::ApiConfig;
+use crate::database::{account_key_for, Database};
+use crate::format::{format_duration, format_hours};
+use crate::i18n::{t, t_with, weekday_short};
+use crate::preferences::load_preferences;
+use crate::projects::resolve_base_url;
+use crate::push_log;
+use crate::statistics::{fetch_hours_with_cache, fetch_streak_with_cache, process_statistics_data, Insight, TrendStatistic};
+
+const DIGEST_CHECK_INTERVAL_SECS: u64 = 60 * 60;
+const DIGEST_TOP_ITEMS: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestDay {
+    pub date: String,
+    pub day_name: String,
+    pub seconds: u64,
+    pub goal_met: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestItem {
+    pub name: String,
+    pub seconds: u64,
+}
+
+// The server only reports the streak as of today, so it describes the day the digest was
+// generated rather than the end of the week it covers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestStreak {
+    pub current: u64,
+    pub longest: u64,
+    #[serde(default)]
+    pub as_of: String,
+    // Relative to the previous stored digest; zero when there is none.
+    pub change: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestGoal {
+    pub daily_goal_hours: f64,
+    pub days_met: usize,
+    pub days_total: usize,
+    pub attainment_percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyDigest {
+    pub week_start: String,
+    pub week_end: String,
+    pub generated_at: String,
+    pub total_seconds: u64,
+    pub previous_week_seconds: u64,
+    pub change_percent: f64,
+    pub days: Vec<DigestDay>,
+    pub best_day: Option<DigestDay>,
+    pub top_projects: Vec<DigestItem>,
+    pub top_languages: Vec<DigestItem>,
+    pub streak: DigestStreak,
+    pub goal: DigestGoal,
+    pub trends: Vec<TrendStatistic>,
+    pub insights: Vec<Insight>,
+}
+
+struct Credentials {
+    base_url: String,
+    access_token: String,
+    account_key: String,
+}
+
+async fn credentials(app: &AppHandle) -> Option<Credentials> {
+    let auth_state = app.state::<Arc<tauri::async_runtime::Mutex<AuthState>>>();
+    let auth_state = auth_state.lock().await;
+    if !auth_state.is_authenticated {
+        return None;
+    }
+    let access_token = auth_state.access_token.clone()?;
+
+    Some(Credentials {
+        base_url: resolve_base_url(app.state::<ApiConfig>().inner()),
+        account_key: account_key_for(auth_state.user_info.as_ref(), Some(&access_token)),
+        access_token,
+    })
+}
+
+// Digests cover a full Monday-to-Sunday week, so the latest one is for the week before this.
+fn last_completed_week_start() -> NaiveDate {
+    week_before(chrono::Local::now().date_naive())
+}
+
+fn week_before(today: NaiveDate) -> NaiveDate {
+    today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64 + 7)
+}
+
+fn change_percent(total_seconds: u64, previous_week_seconds: u64) -> f64 {
+    if previous_week_seconds == 0 {
+        return 0.0;
+    }
+    ((total_seconds as f64 - previous_week_seconds as f64) / previous_week_seconds as f64 * 100.0).round()
+}
+
+fn best_day(days: &[DigestDay]) -> Option<DigestDay> {
+    days.iter().filter(|day| day.seconds > 0).max_by_key(|day| day.seconds).cloned()
+}
+
+fn goal_attainment(daily_goal_hours: f64, days: &[DigestDay]) -> DigestGoal {
+    let days_met = days.iter().filter(|day| day.goal_met).count();
+    DigestGoal {
+        daily_goal_hours,
+        days_met,
+        days_total: days.len(),
+        attainment_percent: if days.is_empty() {
+            0.0
+        } else {
+            (days_met as f64 / days.len() as f64 * 100.0).round()
+        },
+    }
+}
+
+fn top_items(totals: HashMap<String, f64>) -> Vec<DigestItem> {
+    let mut items: Vec<DigestItem> = totals
+        .into_iter()
+        .filter(|(_, seconds)| *seconds >= 1.0)
+        .map(|(name, seconds)| DigestItem {
+            name,
+            seconds: seconds as u64,
+        })
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.seconds));
+    items.truncate(DIGEST_TOP_ITEMS);
+    items
+}
+
+// Summaries give per-day project and language lists; fall back to local heartbeats offline.
+async fn top_projects_and_languages(
+    credentials: &Credentials,
+    week_start: &str,
+    week_end: &str,
+) -> (Vec<DigestItem>, Vec<DigestItem>) {
+    match fetch_summaries(&credentials.base_url, &credentials.access_token, None, week_start, week_end).await {
+        Ok(summaries) => {
+            let projects = summary_totals(&summaries, "projects");
+            let languages = summary_totals(&summaries, "languages");
+            if !projects.is_empty() || !languages.is_empty() {
+                return (top_items(projects), top_items(languages));
+            }
+        }
+        Err(e) => push_log("warn", "backend", format!("Weekly digest falling back to local data: {}", e)),
+    }
+
+    let Ok((start, end)) = local_date_range(week_start, week_end) else {
+        return (Vec::new(), Vec::new());
+    };
+    let projects = local_seconds_by(start, end, |heartbeat| heartbeat.project.clone()).await.unwrap_or_default();
+    let languages = local_seconds_by(start, end, |heartbeat| heartbeat.language.clone()).await.unwrap_or_default();
+    (top_items(projects), top_items(languages))
+}
+
+async fn build_weekly_digest(
+    credentials: &Credentials,
+    week_start: NaiveDate,
+    previous: Option<&WeeklyDigest>,
+) -> Result<WeeklyDigest, String> {
+    let client = reqwest::Client::new();
+    let week_end = week_start + chrono::Duration::days(6);
+    let daily_goal_hours = load_preferences().unwrap_or_default().daily_goal_hours;
+    let goal_seconds = (daily_goal_hours * 3600.0) as u64;
+
+    // A digest is stored for good once generated, so a failed request must not leave zeros
+    // in it; the scheduler tries again on its next check instead.
+    let mut days = Vec::new();
+    let mut daily_hours = serde_json::Map::new();
+    for date in week_start.iter_days().take(7) {
+        let date_str = date.format("%Y-%m-%d").to_string();
+        let seconds = fetch_hours_with_cache(&client, &credentials.base_url, &credentials.access_token, &date_str, &date_str)
+            .await?["total_seconds"]
+            .as_u64()
+            .unwrap_or(0);
+        let day_name = weekday_short(date.weekday());
+
+        daily_hours.insert(date_str.clone(), serde_json::json!({
+            "date": date_str,
+            "day_name": day_name,
+            "hours": seconds as f64 / 3600.0,
+            "seconds": seconds
+        }));
+        days.push(DigestDay {
+            date: date_str,
+            day_name,
+            seconds,
+            goal_met: goal_seconds > 0 && seconds >= goal_seconds,
+        });
+    }
+    let total_seconds: u64 = days.iter().map(|day| day.seconds).sum();
+
+    let previous_start = week_start - chrono::Duration::days(7);
+    let previous_end = week_start - chrono::Duration::days(1);
+    let previous_week_seconds = fetch_hours_with_cache(
+        &client,
+        &credentials.base_url,
+        &credentials.access_token,
+        &previous_start.format("%Y-%m-%d").to_string(),
+        &previous_end.format("%Y-%m-%d").to_string(),
+    )
+    .await?["total_seconds"]
+    .as_u64()
+    .unwrap_or(0);
+
+    let all_time_seconds = fetch_hours_with_cache(
+        &client,
+        &credentials.base_url,
+        &credentials.access_token,
+        &(week_end - chrono::Duration::days(365)).format("%Y-%m-%d").to_string(),
+        &week_end.format("%Y-%m-%d").to_string(),
+    )
+    .await?["total_seconds"]
+    .as_u64()
+    .unwrap_or(0);
+
+    let streak_data = fetch_streak_with_cache(&client, &credentials.base_url, &credentials.access_token).await?;
+    let current_streak = streak_data["streak_days"].as_u64().unwrap_or(0);
+    let longest_streak = streak_data["longest_streak"].as_u64().unwrap_or(0);
+
+    // Same shape get_statistics_data builds, so trends and insights read exactly as in the app.
+    let statistics = process_statistics_data(serde_json::json!({
+        "current_streak": current_streak,
+        "longest_streak": longest_streak,
+        "weekly_stats": {
+            "time_coded_seconds": total_seconds,
+            "daily_hours": daily_hours
+        },
+        "all_time_stats": {
+            "time_coded_seconds": all_time_seconds
+        },
+        "calculated_metrics": {
+            "prev_week_seconds": previous_week_seconds as f64
+        }
+    }))
+    .await?;
+
+    let week_start_str = week_start.format("%Y-%m-%d").to_string();
+    let week_end_str = week_end.format("%Y-%m-%d").to_string();
+    let (top_projects, top_languages) = top_projects_and_languages(credentials, &week_start_str, &week_end_str).await;
+
+    let generated_at = chrono::Local::now();
+    Ok(WeeklyDigest {
+        week_start: week_start_str,
+        week_end: week_end_str,
+        generated_at: generated_at.to_rfc3339(),
+        total_seconds,
+        previous_week_seconds,
+        change_percent: change_percent(total_seconds, previous_week_seconds),
+        best_day: best_day(&days),
+        goal: goal_attainment(daily_goal_hours, &days),
+        days,
+        top_projects,
+        top_languages,
+        streak: DigestStreak {
+            current: current_streak,
+            longest: longest_streak,
+            as_of: generated_at.format("%Y-%m-%d").to_string(),
+            change: previous.map(|p| current_streak as i64 - p.streak.current as i64).unwrap_or(0),
+        },
+        trends: statistics.trends,
+        insights: statistics.insights,
+    })
+}
+
+async fn load_digest(db: &Database, account_key: &str, week_start: &str) -> Result<Option<WeeklyDigest>, String> {
+    match db.get_weekly_digest(account_key, week_start).await? {
+        Some(data) => serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse weekly digest: {}", e)),
+        None => Ok(None),
+    }
+}
+
+async fn generate_and_store(credentials: &Credentials, week_start: NaiveDate) -> Result<WeeklyDigest, String> {
+    let db = Database::new().await?;
+    let previous_start = (week_start - chrono::Duration::days(7)).format("%Y-%m-%d").to_string();
+    let previous = load_digest(&db, &credentials.account_key, &previous_start).await.ok().flatten();
+
+    let digest = build_weekly_digest(credentials, week_start, previous.as_ref()).await?;
+    let data = serde_json::to_string(&digest).map_err(|e| format!("Failed to serialize weekly digest: {}", e))?;
+    db.save_weekly_digest(&credentials.account_key, &digest.week_start, &data).await?;
+
+    push_log("info", "backend", format!("Generated weekly digest for week of {}", digest.week_start));
+    Ok(digest)
+}
+
+fn notify(app: &AppHandle, digest: &WeeklyDigest) {
+    if !load_preferences().unwrap_or_default().notifications_enabled {
+        return;
+    }
+
+    let duration = format_duration(digest.total_seconds as f64);
+    let mut body = if digest.previous_week_seconds > 0 {
+        t_with(
+            "digest.notification_total_change",
+            &[("duration", duration), ("change", format!("{:+.0}", digest.change_percent))],
+        )
+    } else {
+        t_with("digest.notification_total", &[("duration", duration)])
+    };
+    if let Some(project) = digest.top_projects.first() {
+        body.push(' ');
+        body.push_str(&t_with("digest.notification_top_project", &[("project", project.name.clone())]));
+    }
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(t("digest.notification_title"))
+        .body(body)
+        .show()
+    {
+        push_log("warn", "backend", format!("Failed to show weekly digest notification: {}", e));
+    }
+}
+
+// Checks hourly rather than sleeping until Monday so a digest missed while the app was
+// closed or the machine asleep is still produced on the next launch.
+pub fn start_weekly_digest_scheduler(app: &AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(DIGEST_CHECK_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+
+            let Some(credentials) = credentials(&app_handle).await else {
+                continue;
+            };
+            let week_start = last_completed_week_start();
+            let exists = match Database::new().await {
+                Ok(db) => db
+                    .get_weekly_digest(&credentials.account_key, &week_start.format("%Y-%m-%d").to_string())
+                    .await
+                    .map(|digest| digest.is_some())
+                    .unwrap_or(true),
+                Err(_) => true,
+            };
+            if exists {
+                continue;
+            }
+
+            match generate_and_store(&credentials, week_start).await {
+                Ok(digest) => {
+                    let _ = app_handle.emit("weekly-digest-created", &digest);
+                    notify(&app_handle, &digest);
+                }
+                Err(e) => push_log("warn", "backend", format!("Weekly digest generation failed: {}", e)),
+            }
+        }
+    });
+}
+
+fn render_markdown(digest: &WeeklyDigest) -> String {
+    let mut output = format!(
+        "# {}\n\n",
+        t_with("digest.title", &[("start", digest.week_start.clone()), ("end", digest.week_end.clone())])
+    );
+    let duration = format_duration(digest.total_seconds as f64);
+    let total = if digest.previous_week_seconds > 0 {
+        t_with(
+            "digest.total_change",
+            &[
+                ("duration", duration),
+                ("change", format!("{:+.0}", digest.change_percent)),
+                ("previous", format_duration(digest.previous_week_seconds as f64)),
+            ],
+        )
+    } else {
+        t_with("digest.total", &[("duration", duration)])
+    };
+    output.push_str(&format!("- {}\n", total));
+    if let Some(day) = &digest.best_day {
+        let best_day = t_with(
+            "digest.best_day",
+            &[
+                ("day", day.day_name.clone()),
+                ("date", day.date.clone()),
+                ("duration", format_duration(day.seconds as f64)),
+            ],
+        );
+        output.push_str(&format!("- {}\n", best_day));
+    }
+    // Digests stored before the streak date was recorded fall back to the generation time.
+    let streak_date = if digest.streak.as_of.is_empty() {
+        digest.generated_at.get(..10).unwrap_or_default().to_string()
+    } else {
+        digest.streak.as_of.clone()
+    };
+    let streak = t_with(
+        "digest.streak",
+        &[
+            ("date", streak_date),
+            ("current", digest.streak.current.to_string()),
+            ("change", format!("{:+}", digest.streak.change)),
+            ("longest", digest.streak.longest.to_string()),
+        ],
+    );
+    output.push_str(&format!("- {}\n", streak));
+    let goal = t_with(
+        "digest.goal",
+        &[
+            ("goal", format_hours(digest.goal.daily_goal_hours)),
+            ("met", digest.goal.days_met.to_string()),
+            ("total", digest.goal.days_total.to_string()),
+            ("percent", format!("{:.0}", digest.goal.attainment_percent)),
+        ],
+    );
+    output.push_str(&format!("- {}\n", goal));
+
+    output.push_str(&format!(
+        "\n## {}\n\n| {} | {} | {} |\n|---|---|---|\n",
+        t("digest.days"),
+        t("digest.day"),
+        t("digest.time"),
+        t("digest.goal_column")
+    ));
+    let goal_met = t("digest.goal_met");
+    for day in &digest.days {
+        output.push_str(&format!(
+            "| {} {} | {} | {} |\n",
+            day.day_name,
+            day.date,
+            format_duration(day.seconds as f64),
+            if day.goal_met { goal_met.as_str() } else { "" }
+        ));
+    }
+
+    for (title, items) in [("digest.top_projects", &digest.top_projects), ("digest.top_languages", &digest.top_languages)] {
+        if items.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("\n## {}\n\n", t(title)));
+        for (i, item) in items.iter().enumerate() {
+            output.push_str(&format!("{}. {} ({})\n", i + 1, item.name.replace('|', "\\|"), format_duration(item.seconds as f64)));
+        }
+    }
+
+    if !digest.insights.is_empty() {
+        output.push_str(&format!("\n## {}\n\n", t("digest.insights")));
+        for insight in &digest.insights {
+            output.push_str(&format!("- **{}**: {}\n", insight.title, insight.description));
+        }
+    }
+
+    output
+}
+
+async fn require_credentials(app: &AppHandle) -> Result<Credentials, String> {
+    credentials(app).await.ok_or_else(|| "Not authenticated".to_string())
+}
+
+#[tauri::command]
+pub async fn get_weekly_digests(app_handle: AppHandle) -> Result<Vec<WeeklyDigest>, String> {
+    let credentials = require_credentials(&app_handle).await?;
+    let db = Database::new().await?;
+    Ok(db
+        .list_weekly_digests(&credentials.account_key)
+        .await?
+        .iter()
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect())
+}
+
+#[tauri::command]
+pub async fn generate_weekly_digest(week_start: Option<String>, app_handle: AppHandle) -> Result<WeeklyDigest, String> {
+    let credentials = require_credentials(&app_handle).await?;
+    let week_start = match week_start {
+        Some(value) => {
+            let date = NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|e| format!("Invalid date '{}': {}", value, e))?;
+            date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)
+        }
+        None => last_completed_week_start(),
+    };
+    generate_and_store(&credentials, week_start).await
+}
+
+#[tauri::command]
+pub async fn export_weekly_digest(week_start: String, path: String, app_handle: AppHandle) -> Result<String, String> {
+    let credentials = require_credentials(&app_handle).await?;
+    let db = Database::new().await?;
+    let digest = load_digest(&db, &credentials.account_key, &week_start)
+        .await?
+        .ok_or_else(|| format!("No weekly digest for week of {}", week_start))?;
+
+    fs::write(&path, render_markdown(&digest)).map_err(|e| format!("Failed to write weekly digest: {}", e))?;
+    push_log("info", "backend", format!("Weekly digest exported to {}", path));
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, seconds: u64, goal_met: bool) -> DigestDay {
+        DigestDay {
+            date: date.to_string(),
+            day_name: "Mon".to_string(),
+            seconds,
+            goal_met,
+        }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn last_completed_week_is_the_monday_before_this_week() {
+        // 2024-03-11 is a Monday.
+        assert_eq!(week_before(date("2024-03-11")), date("2024-03-04"));
+        assert_eq!(week_before(date("2024-03-13")), date("2024-03-04"));
+        assert_eq!(week_before(date("2024-03-17")), date("2024-03-04"));
+        assert_eq!(week_before(date("2024-03-18")), date("2024-03-11"));
+        assert_eq!(week_before(date("2025-01-01")), date("2024-12-23"));
+    }
+
+    #[test]
+    fn change_is_rounded_and_zero_without_a_previous_week() {
+        assert_eq!(change_percent(7200, 0), 0.0);
+        assert_eq!(change_percent(0, 3600), -100.0);
+        assert_eq!(change_percent(5400, 3600), 50.0);
+        assert_eq!(change_percent(4000, 3000), 33.0);
+        assert_eq!(change_percent(3600, 3600), 0.0);
+    }
+
+    #[test]
+    fn best_day_ignores_empty_days() {
+        assert!(best_day(&[day("2024-03-04", 0, false), day("2024-03-05", 0, false)]).is_none());
+
+        let days = [day("2024-03-04", 600, false), day("2024-03-05", 7200, true), day("2024-03-06", 1800, false)];
+        assert_eq!(best_day(&days).unwrap().date, "2024-03-05");
+    }
+
+    #[test]
+    fn goal_attainment_counts_days_that_met_the_goal() {
+        let days: Vec<DigestDay> = (0..7).map(|i| day(&format!("2024-03-{:02}", 4 + i), 3600, i < 3)).collect();
+        let goal = goal_attainment(1.0, &days);
+        assert_eq!((goal.days_met, goal.days_total), (3, 7));
+        assert_eq!(goal.attainment_percent, 43.0);
+
+        assert_eq!(goal_attainment(1.0, &[]).attainment_percent, 0.0);
+    }
+
+    fn digest() -> WeeklyDigest {
+        let days = vec![day("2024-03-04", 7200, true), day("2024-03-05", 0, false)];
+        WeeklyDigest {
+            week_start: "2024-03-04".to_string(),
+            week_end: "2024-03-10".to_string(),
+            generated_at: "2024-03-11T09:00:00+00:00".to_string(),
+            total_seconds: 7200,
+            previous_week_seconds: 3600,
+            change_percent: 100.0,
+            best_day: best_day(&days),
+            goal: goal_attainment(2.0, &days),
+            days,
+            top_projects: vec![DigestItem { name: "a|b".to_string(), seconds: 7200 }],
+            top_languages: Vec::new(),
+            streak: DigestStreak {
+                current: 4,
+                longest: 9,
+                as_of: String::new(),
+                change: 2,
+            },
+            trends: Vec::new(),
+            insights: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn markdown_lists_the_summary_days_and_items() {
+        let _guard = crate::test_support::isolated();
+        crate::i18n::init(crate::i18n::DEFAULT_LOCALE);
+        let markdown = render_markdown(&digest());
+
+        assert!(markdown.starts_with("# Weekly digest: 2024-03-04 to 2024-03-10\n"), "{}", markdown);
+        assert!(markdown.contains("+100%"), "{}", markdown);
+        assert!(markdown.contains("Streak on 2024-03-11: 4 days"), "{}", markdown);
+        assert!(markdown.contains("met on 1 of 2 days (50%)"), "{}", markdown);
+        assert!(markdown.contains("| Mon 2024-03-04 |"), "{}", markdown);
+        assert!(markdown.contains("1. a\\|b ("), "{}", markdown);
+        assert!(!markdown.contains("Top languages"), "{}", markdown);
+        assert!(!markdown.contains("Insights"), "{}", markdown);
+    }
+}
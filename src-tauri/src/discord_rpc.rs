@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::sync::Mutex;
 
+use crate::i18n::t;
 use crate::session::HeartbeatData;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -125,12 +126,15 @@ impl DiscordRpcService {
         }
 
         
+        // Discord already counts up from the start timestamp; a duration baked into the text
+        // would only go stale between updates.
+        let small_text = t("discord.coding");
         discord_activity = discord_activity.assets(
             activity::Assets::new()
                 .large_image("kubetime")
                 .large_text("KubeTime - Time Tracking")
                 .small_image("coding")
-                .small_text("Coding"),
+                .small_text(&small_text),
         );
 
         client
@@ -170,7 +174,7 @@ impl DiscordRpcService {
             project_name: heartbeat_data
                 .project
                 .clone()
-                .unwrap_or_else(|| "Unknown Project".to_string()),
+                .unwrap_or_else(|| t("tray.unknown_project")),
             language: heartbeat_data.language.clone(),
             editor: heartbeat_data.editor.clone(),
             entity: heartbeat_data.entity.clone(),
@@ -189,7 +193,7 @@ impl DiscordRpcService {
             project_name: heartbeat_data
                 .project
                 .clone()
-                .unwrap_or_else(|| "Unknown Project".to_string()),
+                .unwrap_or_else(|| t("tray.unknown_project")),
             language: heartbeat_data.language.clone(),
             editor: heartbeat_data.editor.clone(),
             entity: heartbeat_data.entity.clone(),
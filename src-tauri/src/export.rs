@@ -0,0 +1,780 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::activity::{dates_between, heartbeat_durations, local_date_range, DateRange, HEARTBEAT_TIMEOUT_SECS};
+use crate::auth::AuthState;
+use crate::breakdown::{fetch_summaries, BreakdownSource};
+use crate::config::ApiConfig;
+use crate::database::{Database, ProjectMetadata};
+use crate::projects::{access_token, fetch_json, resolve_base_url};
+use crate::format::{format_duration_as, DurationStyle};
+use crate::push_log;
+
+const ICS_PRODID: &str = "-//Hack Club//Hackatime Desktop//EN";
+const MAX_SESSION_EXPORT_DAYS: usize = 366;
+const DURATION_REQUESTS_IN_FLIGHT: usize = 7;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Ics,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportMode {
+    #[default]
+    Sessions,
+    DailyTotals,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportGrouping {
+    #[default]
+    Day,
+    Project,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum RoundingRule {
+    #[default]
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "nearest_6")]
+    Nearest6,
+    #[serde(rename = "nearest_15")]
+    Nearest15,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimesheetOptions {
+    #[serde(default, flatten)]
+    pub range: DateRange,
+    #[serde(default)]
+    pub mode: ExportMode,
+    #[serde(default)]
+    pub group_by: ExportGrouping,
+    #[serde(default)]
+    pub rounding: RoundingRule,
+    #[serde(default)]
+    pub projects: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    // Without this a failed or unauthenticated server request fails the export instead of
+    // quietly producing an undercounted timesheet from local heartbeats.
+    #[serde(default)]
+    pub allow_local_fallback: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimesheetEntry {
+    pub date: String,
+    pub project: String,
+    pub tags: Vec<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub seconds: f64,
+    pub billable_seconds: f64,
+    pub duration: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimesheetGroup {
+    pub key: String,
+    pub billable_seconds: f64,
+    pub entries: Vec<TimesheetEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Timesheet {
+    pub start_date: String,
+    pub end_date: String,
+    pub mode: ExportMode,
+    pub group_by: ExportGrouping,
+    pub rounding: RoundingRule,
+    // Local heartbeats are only sampled while the app runs, so they undercount.
+    pub source: BreakdownSource,
+    pub generated_at: String,
+    pub billable_seconds: f64,
+    pub groups: Vec<TimesheetGroup>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimesheetExportResult {
+    pub path: String,
+    pub entries: usize,
+    pub billable_seconds: f64,
+}
+
+struct CodingSession {
+    project: String,
+    start: f64,
+    end: f64,
+    seconds: f64,
+    open: bool,
+}
+
+impl RoundingRule {
+    fn apply(self, seconds: f64) -> f64 {
+        let step = match self {
+            RoundingRule::None => return seconds,
+            RoundingRule::Nearest6 => 6.0 * 60.0,
+            RoundingRule::Nearest15 => 15.0 * 60.0,
+        };
+        (seconds / step).round() * step
+    }
+}
+
+// Back-to-back heartbeats for the same project form one session; a project switch or a gap
+// longer than the heartbeat timeout starts the next one.
+async fn local_sessions(start: i64, end: i64) -> Result<Vec<CodingSession>, String> {
+    let heartbeats = Database::new().await?.get_heartbeats_between(start, end).await?;
+    let durations = heartbeat_durations(&heartbeats);
+
+    let mut sessions: Vec<CodingSession> = Vec::new();
+    for (heartbeat, duration) in heartbeats.iter().zip(durations) {
+        let Some(project) = heartbeat.project.as_deref() else {
+            continue;
+        };
+
+        // A capped duration means the user stepped away after the previous heartbeat.
+        match sessions.last_mut() {
+            Some(session) if session.project == project && session.open => {
+                session.end = heartbeat.time + duration;
+                session.seconds += duration;
+                session.open = duration < HEARTBEAT_TIMEOUT_SECS;
+            }
+            _ => sessions.push(CodingSession {
+                project: project.to_string(),
+                start: heartbeat.time,
+                end: heartbeat.time + duration,
+                seconds: duration,
+                open: duration < HEARTBEAT_TIMEOUT_SECS,
+            }),
+        }
+    }
+
+    sessions.retain(|session| session.seconds > 0.0);
+    Ok(sessions)
+}
+
+fn local_daily_totals(sessions: &[CodingSession]) -> BTreeMap<(String, String), f64> {
+    let mut totals: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for session in sessions {
+        let date = local_time(session.start).format("%Y-%m-%d").to_string();
+        *totals.entry((date, session.project.clone())).or_default() += session.seconds;
+    }
+    totals
+}
+
+// WakaTime-compatible durations are the server's own sessions, built from every heartbeat
+// the user's editors sent. The endpoint only takes a single day, so days are fetched a few
+// at a time and every failed day is reported rather than just the first.
+async fn fetch_server_sessions(
+    base_url: &str,
+    access_token: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<CodingSession>, String> {
+    let dates = dates_between(start_date, end_date)?;
+    if dates.len() > MAX_SESSION_EXPORT_DAYS {
+        return Err(format!(
+            "Session exports cover at most {} days ({} requested); export daily totals for longer ranges",
+            MAX_SESSION_EXPORT_DAYS,
+            dates.len()
+        ));
+    }
+
+    let mut responses = Vec::new();
+    let mut failures = Vec::new();
+    for batch in dates.chunks(DURATION_REQUESTS_IN_FLIGHT) {
+        let mut requests = tokio::task::JoinSet::new();
+        for date in batch {
+            let url = format!("{}/api/v1/users/current/durations?date={}", base_url, date);
+            let access_token = access_token.to_string();
+            let date = date.clone();
+            requests.spawn(async move { (date, fetch_json(&url, &access_token, "durations").await) });
+        }
+        while let Some(joined) = requests.join_next().await {
+            let (date, result) = joined.map_err(|e| format!("Durations request did not finish: {}", e))?;
+            match result {
+                Ok(response) => responses.push(response),
+                Err(e) => failures.push(format!("{}: {}", date, e)),
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        failures.sort();
+        return Err(format!(
+            "Failed to fetch durations for {} of {} days: {}",
+            failures.len(),
+            dates.len(),
+            failures.join("; ")
+        ));
+    }
+
+    let mut sessions = Vec::new();
+    for response in &responses {
+        for item in response["data"].as_array().into_iter().flatten() {
+            let (Some(project), Some(time), Some(duration)) =
+                (item["project"].as_str(), item["time"].as_f64(), item["duration"].as_f64())
+            else {
+                continue;
+            };
+            sessions.push(CodingSession {
+                project: project.to_string(),
+                start: time,
+                end: time + duration,
+                seconds: duration,
+                open: false,
+            });
+        }
+    }
+
+    sessions.retain(|session| session.seconds > 0.0);
+    sessions.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(sessions)
+}
+
+async fn fetch_server_daily_totals(
+    base_url: &str,
+    access_token: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<BTreeMap<(String, String), f64>, String> {
+    let response = fetch_summaries(base_url, access_token, None, start_date, end_date).await?;
+
+    let mut totals: BTreeMap<(String, String), f64> = BTreeMap::new();
+    for day in response["data"].as_array().into_iter().flatten() {
+        let Some(date) = day["range"]["date"]
+            .as_str()
+            .or_else(|| day["range"]["start"].as_str().and_then(|start| start.get(..10)))
+        else {
+            continue;
+        };
+        for project in day["projects"].as_array().into_iter().flatten() {
+            let (Some(name), Some(seconds)) = (project["name"].as_str(), project["total_seconds"].as_f64()) else {
+                continue;
+            };
+            if seconds > 0.0 {
+                *totals.entry((date.to_string(), name.to_string())).or_default() += seconds;
+            }
+        }
+    }
+    Ok(totals)
+}
+
+async fn server_access(
+    api_config: &ApiConfig,
+    state: &Arc<tauri::async_runtime::Mutex<AuthState>>,
+) -> Result<(String, String), String> {
+    let token = access_token(state).await?;
+    Ok((resolve_base_url(api_config), token))
+}
+
+// `None` means the caller should build the timesheet from local heartbeats, which only
+// happens when the export explicitly allows it.
+fn use_server<T>(result: Result<T, String>, is_empty: fn(&T) -> bool, allow_local_fallback: bool) -> Result<Option<T>, String> {
+    match result {
+        Ok(data) if !allow_local_fallback || !is_empty(&data) => Ok(Some(data)),
+        Ok(_) => {
+            push_log("debug", "backend", "No server activity for timesheet, using local heartbeats".to_string());
+            Ok(None)
+        }
+        Err(e) if allow_local_fallback => {
+            push_log("warn", "backend", format!("Falling back to local heartbeats for timesheet: {}", e));
+            Ok(None)
+        }
+        Err(e) => Err(format!("Failed to load server activity for timesheet: {}", e)),
+    }
+}
+
+// Exports are read by other tools, so they keep one fixed, locale-independent style
+// whatever the UI is set to.
+fn export_duration(seconds: f64) -> String {
+    format_duration_as(seconds, DurationStyle::Clock)
+}
+
+fn local_time(timestamp: f64) -> chrono::DateTime<chrono::Local> {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .unwrap_or_default()
+        .with_timezone(&chrono::Local)
+}
+
+fn matches_filters(project: &str, options: &TimesheetOptions, metadata: &HashMap<String, ProjectMetadata>) -> bool {
+    if !options.projects.is_empty() && !options.projects.iter().any(|p| p == project) {
+        return false;
+    }
+    if options.tags.is_empty() {
+        return true;
+    }
+    metadata.get(project).is_some_and(|metadata| {
+        metadata
+            .tags
+            .iter()
+            .any(|tag| options.tags.iter().any(|wanted| wanted.eq_ignore_ascii_case(tag)))
+    })
+}
+
+pub async fn build_timesheet(
+    options: &TimesheetOptions,
+    api_config: &ApiConfig,
+    state: &Arc<tauri::async_runtime::Mutex<AuthState>>,
+) -> Result<Timesheet, String> {
+    let (start_date, end_date) = options.range.clone().resolve();
+    let (start, end) = local_date_range(&start_date, &end_date)?;
+    let metadata = Database::new().await?.get_all_project_metadata().await?;
+    let tags_for = |project: &str| metadata.get(project).map(|m| m.tags.clone()).unwrap_or_default();
+    let server = server_access(api_config, state).await;
+
+    let (source, entries): (BreakdownSource, Vec<TimesheetEntry>) = match options.mode {
+        ExportMode::Sessions => {
+            let server_sessions = match &server {
+                Ok((base_url, token)) => fetch_server_sessions(base_url, token, &start_date, &end_date).await,
+                Err(e) => Err(e.clone()),
+            };
+            let server_sessions = use_server(server_sessions, Vec::is_empty, options.allow_local_fallback)?;
+            let (source, sessions) = match server_sessions {
+                Some(sessions) => (BreakdownSource::Server, sessions),
+                None => (BreakdownSource::Local, local_sessions(start, end).await?),
+            };
+
+            let entries = sessions
+                .iter()
+                .filter(|session| matches_filters(&session.project, options, &metadata))
+                .map(|session| TimesheetEntry {
+                    date: local_time(session.start).format("%Y-%m-%d").to_string(),
+                    project: session.project.clone(),
+                    tags: tags_for(&session.project),
+                    start: Some(local_time(session.start).to_rfc3339()),
+                    end: Some(local_time(session.end).to_rfc3339()),
+                    seconds: session.seconds,
+                    billable_seconds: options.rounding.apply(session.seconds),
+                    duration: export_duration(session.seconds),
+                })
+                .collect();
+            (source, entries)
+        }
+        ExportMode::DailyTotals => {
+            let server_totals = match &server {
+                Ok((base_url, token)) => fetch_server_daily_totals(base_url, token, &start_date, &end_date).await,
+                Err(e) => Err(e.clone()),
+            };
+            let server_totals = use_server(server_totals, BTreeMap::is_empty, options.allow_local_fallback)?;
+            let (source, totals) = match server_totals {
+                Some(totals) => (BreakdownSource::Server, totals),
+                None => (BreakdownSource::Local, local_daily_totals(&local_sessions(start, end).await?)),
+            };
+
+            let entries = totals
+                .into_iter()
+                .filter(|((_, project), _)| matches_filters(project, options, &metadata))
+                .map(|((date, project), seconds)| TimesheetEntry {
+                    date,
+                    tags: tags_for(&project),
+                    project,
+                    start: None,
+                    end: None,
+                    duration: export_duration(seconds),
+                    seconds,
+                    billable_seconds: options.rounding.apply(seconds),
+                })
+                .collect();
+            (source, entries)
+        }
+    };
+
+    let mut grouped: BTreeMap<String, Vec<TimesheetEntry>> = BTreeMap::new();
+    for entry in entries {
+        let key = match options.group_by {
+            ExportGrouping::Day => entry.date.clone(),
+            ExportGrouping::Project => entry.project.clone(),
+        };
+        grouped.entry(key).or_default().push(entry);
+    }
+
+    let groups: Vec<TimesheetGroup> = grouped
+        .into_iter()
+        .map(|(key, entries)| TimesheetGroup {
+            key,
+            billable_seconds: entries.iter().map(|e| e.billable_seconds).sum(),
+            entries,
+        })
+        .collect();
+
+    Ok(Timesheet {
+        start_date,
+        end_date,
+        mode: options.mode,
+        group_by: options.group_by,
+        rounding: options.rounding,
+        source,
+        generated_at: chrono::Local::now().to_rfc3339(),
+        billable_seconds: groups.iter().map(|g| g.billable_seconds).sum(),
+        groups,
+    })
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn render_csv(timesheet: &Timesheet) -> String {
+    let mut output = String::from("group,date,project,tags,start,end,hours,billable_hours,duration,source\r\n");
+    for group in &timesheet.groups {
+        for entry in &group.entries {
+            let row = [
+                csv_field(&group.key),
+                entry.date.clone(),
+                csv_field(&entry.project),
+                csv_field(&entry.tags.join(";")),
+                entry.start.clone().unwrap_or_default(),
+                entry.end.clone().unwrap_or_default(),
+                format!("{:.2}", entry.seconds / 3600.0),
+                format!("{:.2}", entry.billable_seconds / 3600.0),
+                csv_field(&entry.duration),
+                timesheet.source.as_str().to_string(),
+            ];
+            output.push_str(&row.join(","));
+            output.push_str("\r\n");
+        }
+    }
+    output
+}
+
+fn ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// RFC 5545 content lines are folded at 75 octets, continuing with a leading space.
+fn ics_line(output: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            width = 1;
+        }
+        output.push(c);
+        width += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+fn ics_timestamp(value: &str) -> Option<String> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc).format("%Y%m%dT%H%M%SZ").to_string())
+}
+
+// Calendars need real start and end times, so daily totals are not exportable as .ics.
+fn render_ics(timesheet: &Timesheet) -> Result<String, String> {
+    if timesheet.mode != ExportMode::Sessions {
+        return Err("iCalendar export requires session mode".to_string());
+    }
+
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut output = String::new();
+    ics_line(&mut output, "BEGIN:VCALENDAR");
+    ics_line(&mut output, "VERSION:2.0");
+    ics_line(&mut output, &format!("PRODID:{}", ICS_PRODID));
+    ics_line(&mut output, "CALSCALE:GREGORIAN");
+    ics_line(&mut output, &format!("X-HACKATIME-SOURCE:{}", timesheet.source.as_str()));
+
+    for entry in timesheet.groups.iter().flat_map(|group| &group.entries) {
+        let (Some(start), Some(end)) = (
+            entry.start.as_deref().and_then(ics_timestamp),
+            entry.end.as_deref().and_then(ics_timestamp),
+        ) else {
+            continue;
+        };
+
+        ics_line(&mut output, "BEGIN:VEVENT");
+        ics_line(&mut output, &format!("UID:{}-{}@hackatime-desktop", start, ics_text(&entry.project).replace(' ', "-")));
+        ics_line(&mut output, &format!("DTSTAMP:{}", stamp));
+        ics_line(&mut output, &format!("DTSTART:{}", start));
+        ics_line(&mut output, &format!("DTEND:{}", end));
+        ics_line(&mut output, &format!("SUMMARY:{}", ics_text(&entry.project)));
+        ics_line(
+            &mut output,
+            &format!("DESCRIPTION:{}", ics_text(&format!(
+                "Duration: {}\nBillable hours: {:.2}{}",
+                entry.duration,
+                entry.billable_seconds / 3600.0,
+                if timesheet.source == BreakdownSource::Local { "\nSource: local heartbeats" } else { "" }
+            ))),
+        );
+        if !entry.tags.is_empty() {
+            let tags: Vec<String> = entry.tags.iter().map(|tag| ics_text(tag)).collect();
+            ics_line(&mut output, &format!("CATEGORIES:{}", tags.join(",")));
+        }
+        ics_line(&mut output, "END:VEVENT");
+    }
+
+    ics_line(&mut output, "END:VCALENDAR");
+    Ok(output)
+}
+
+#[tauri::command]
+pub async fn preview_timesheet(
+    options: TimesheetOptions,
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<Timesheet, String> {
+    build_timesheet(&options, &api_config, state.inner()).await
+}
+
+#[tauri::command]
+pub async fn export_timesheet(
+    options: TimesheetOptions,
+    format: ExportFormat,
+    path: String,
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<TimesheetExportResult, String> {
+    let timesheet = build_timesheet(&options, &api_config, state.inner()).await?;
+
+    let content = match format {
+        ExportFormat::Csv => render_csv(&timesheet),
+        ExportFormat::Json => serde_json::to_string_pretty(&timesheet)
+            .map_err(|e| format!("Failed to serialize timesheet: {}", e))?,
+        ExportFormat::Ics => render_ics(&timesheet)?,
+    };
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write timesheet: {}", e))?;
+
+    let entries = timesheet.groups.iter().map(|group| group.entries.len()).sum();
+    push_log("info", "backend", format!("Exported {} timesheet entries to {}", entries, path));
+    Ok(TimesheetExportResult {
+        path,
+        entries,
+        billable_seconds: timesheet.billable_seconds,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn rounding_rules_round_to_the_nearest_step() {
+        assert_eq!(RoundingRule::None.apply(1234.5), 1234.5);
+
+        assert_eq!(RoundingRule::Nearest6.apply(179.0), 0.0);
+        assert_eq!(RoundingRule::Nearest6.apply(180.0), 360.0);
+        assert_eq!(RoundingRule::Nearest6.apply(540.0), 720.0);
+        assert_eq!(RoundingRule::Nearest6.apply(3600.0), 3600.0);
+
+        assert_eq!(RoundingRule::Nearest15.apply(449.0), 0.0);
+        assert_eq!(RoundingRule::Nearest15.apply(450.0), 900.0);
+        assert_eq!(RoundingRule::Nearest15.apply(1349.0), 900.0);
+        assert_eq!(RoundingRule::Nearest15.apply(1350.0), 1800.0);
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("hackatime"), "hackatime");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    fn folded(line: &str) -> Vec<String> {
+        let mut output = String::new();
+        ics_line(&mut output, line);
+        let output = output.strip_suffix("\r\n").expect("content line ends with CRLF");
+        output.split("\r\n").map(str::to_string).collect()
+    }
+
+    #[test]
+    fn ics_lines_fold_at_75_octets() {
+        assert_eq!(folded(&"a".repeat(75)).len(), 1);
+
+        let lines = folded(&"a".repeat(76));
+        assert_eq!(lines, vec!["a".repeat(75), " a".to_string()]);
+    }
+
+    #[test]
+    fn ics_folding_never_splits_multibyte_characters() {
+        for text in ["é".repeat(80), "日本語のプロジェクト".repeat(10), format!("{}🚀🚀🚀", "a".repeat(73))] {
+            let line = format!("SUMMARY:{}", text);
+            let lines = folded(&line);
+
+            assert!(lines.len() > 1);
+            assert!(lines.iter().all(|l| l.len() <= 75), "{:?}", lines);
+            assert!(lines[1..].iter().all(|l| l.starts_with(' ')));
+            let unfolded: String = lines
+                .iter()
+                .enumerate()
+                .map(|(i, l)| if i == 0 { l.as_str() } else { &l[1..] })
+                .collect();
+            assert_eq!(unfolded, line);
+        }
+    }
+
+    fn authenticated() -> Arc<tauri::async_runtime::Mutex<AuthState>> {
+        Arc::new(tauri::async_runtime::Mutex::new(AuthState {
+            is_authenticated: true,
+            access_token: Some("token".to_string()),
+            user_info: None,
+            credential_type: Default::default(),
+        }))
+    }
+
+    fn options_for(date: &str, mode: ExportMode) -> TimesheetOptions {
+        options_between(date, date, mode)
+    }
+
+    fn options_between(start_date: &str, end_date: &str, mode: ExportMode) -> TimesheetOptions {
+        TimesheetOptions {
+            range: DateRange {
+                start_date: Some(start_date.to_string()),
+                end_date: Some(end_date.to_string()),
+            },
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sessions_come_from_server_durations() {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/users/current/durations"))
+                .and(query_param("date", "2024-03-04"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [
+                        { "project": "hackatime", "time": 1709546400.0, "duration": 5400.0 },
+                        { "project": "desktop", "time": 1709553600.0, "duration": 1800.0 },
+                    ]
+                })))
+                .mount(&server)
+                .await;
+            let api_config = ApiConfig { base_url: server.uri() };
+
+            let timesheet = build_timesheet(&options_for("2024-03-04", ExportMode::Sessions), &api_config, &authenticated())
+                .await
+                .unwrap();
+
+            assert_eq!(timesheet.source, BreakdownSource::Server);
+            assert_eq!(timesheet.billable_seconds, 7200.0);
+            assert!(render_csv(&timesheet).lines().skip(1).all(|row| row.ends_with(",server")));
+        });
+    }
+
+    #[test]
+    fn failed_days_are_all_reported() {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let server = MockServer::start().await;
+            for date in ["2024-03-05", "2024-03-07"] {
+                Mock::given(method("GET"))
+                    .and(path("/api/v1/users/current/durations"))
+                    .and(query_param("date", date))
+                    .respond_with(ResponseTemplate::new(503))
+                    .mount(&server)
+                    .await;
+            }
+            Mock::given(method("GET"))
+                .and(path("/api/v1/users/current/durations"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "data": [] })))
+                .mount(&server)
+                .await;
+            let api_config = ApiConfig { base_url: server.uri() };
+
+            let error = build_timesheet(
+                &options_between("2024-03-04", "2024-03-08", ExportMode::Sessions),
+                &api_config,
+                &authenticated(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(error.contains("2 of 5 days"), "{}", error);
+            assert!(error.contains("2024-03-05") && error.contains("2024-03-07"), "{}", error);
+            assert!(!error.contains("2024-03-06"), "{}", error);
+        });
+    }
+
+    #[test]
+    fn session_exports_are_capped_at_a_year() {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let server = MockServer::start().await;
+            let api_config = ApiConfig { base_url: server.uri() };
+
+            let error = build_timesheet(
+                &options_between("2023-01-01", "2024-12-31", ExportMode::Sessions),
+                &api_config,
+                &authenticated(),
+            )
+            .await
+            .unwrap_err();
+
+            assert!(error.contains("at most 366 days"), "{}", error);
+            assert!(server.received_requests().await.unwrap().is_empty());
+        });
+    }
+
+    async fn failing_summaries() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/users/current/summaries"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[test]
+    fn failed_server_requests_fail_the_export_by_default() {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let server = failing_summaries().await;
+            let api_config = ApiConfig { base_url: server.uri() };
+
+            let result = build_timesheet(&options_for("2024-03-04", ExportMode::DailyTotals), &api_config, &authenticated()).await;
+
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn allowed_local_fallback_is_marked_as_local() {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let server = failing_summaries().await;
+            let api_config = ApiConfig { base_url: server.uri() };
+            let options = TimesheetOptions {
+                allow_local_fallback: true,
+                ..options_for("2024-03-04", ExportMode::DailyTotals)
+            };
+
+            let timesheet = build_timesheet(&options, &api_config, &authenticated()).await.unwrap();
+
+            assert_eq!(timesheet.source, BreakdownSource::Local);
+            let json = serde_json::to_value(&timesheet).unwrap();
+            assert_eq!(json["source"], "local");
+        });
+    }
+}
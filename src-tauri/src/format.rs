@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+use crate::i18n::{format_number, t_with};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DurationStyle {
+    /// "2.5h"
+    Decimal,
+    /// "2h 30m"
+    #[default]
+    HoursMinutes,
+    /// "02:30"
+    Clock,
+}
+
+static DURATION_STYLE: RwLock<DurationStyle> = RwLock::new(DurationStyle::HoursMinutes);
+
+pub fn init(style: DurationStyle) {
+    if let Ok(mut current) = DURATION_STYLE.write() {
+        *current = style;
+    }
+}
+
+pub fn duration_style() -> DurationStyle {
+    DURATION_STYLE.read().map(|style| *style).unwrap_or_default()
+}
+
+pub fn format_duration_as(seconds: f64, style: DurationStyle) -> String {
+    let seconds = seconds.max(0.0);
+    let total_minutes = (seconds / 60.0).round() as u64;
+    let (hours, minutes) = (total_minutes / 60, total_minutes % 60);
+
+    match style {
+        DurationStyle::Decimal => t_with("format.hours_decimal", &[("value", format_number(seconds / 3600.0, 1))]),
+        DurationStyle::HoursMinutes if hours > 0 => t_with(
+            "format.hours_minutes",
+            &[("hours", format_number(hours as f64, 0)), ("minutes", minutes.to_string())],
+        ),
+        DurationStyle::HoursMinutes => t_with("format.minutes", &[("minutes", minutes.to_string())]),
+        DurationStyle::Clock => format!("{:02}:{:02}", hours, minutes),
+    }
+}
+
+// Formats with the style chosen in preferences.
+pub fn format_duration(seconds: f64) -> String {
+    format_duration_as(seconds, duration_style())
+}
+
+pub fn format_hours(hours: f64) -> String {
+    format_duration(hours * 3600.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_format_in_every_style() {
+        let _guard = crate::test_support::isolated();
+
+        assert_eq!(format_duration_as(9000.0, DurationStyle::Decimal), "2.5h");
+        assert_eq!(format_duration_as(9000.0, DurationStyle::HoursMinutes), "2h 30m");
+        assert_eq!(format_duration_as(9000.0, DurationStyle::Clock), "02:30");
+
+        assert_eq!(format_duration_as(1500.0, DurationStyle::Decimal), "0.4h");
+        assert_eq!(format_duration_as(1500.0, DurationStyle::HoursMinutes), "25m");
+        assert_eq!(format_duration_as(1500.0, DurationStyle::Clock), "00:25");
+
+        assert_eq!(format_duration_as(360_000.0, DurationStyle::Clock), "100:00");
+        assert_eq!(format_duration_as(-30.0, DurationStyle::HoursMinutes), "0m");
+    }
+
+    // 59.5 minutes rounds up to a full hour instead of showing "60m".
+    #[test]
+    fn minutes_round_over_into_the_next_hour() {
+        let _guard = crate::test_support::isolated();
+
+        assert_eq!(format_duration_as(3570.0, DurationStyle::Decimal), "1.0h");
+        assert_eq!(format_duration_as(3570.0, DurationStyle::HoursMinutes), "1h 0m");
+        assert_eq!(format_duration_as(3570.0, DurationStyle::Clock), "01:00");
+
+        assert_eq!(format_duration_as(3569.0, DurationStyle::HoursMinutes), "59m");
+        assert_eq!(format_duration_as(3569.0, DurationStyle::Clock), "00:59");
+    }
+
+    #[test]
+    fn clock_style_ignores_the_locale() {
+        let _guard = crate::test_support::isolated();
+        crate::i18n::init("de");
+        let decimal = format_duration_as(9000.0, DurationStyle::Decimal);
+        let clock = format_duration_as(4_500_000.0, DurationStyle::Clock);
+        crate::i18n::init(crate::i18n::DEFAULT_LOCALE);
+
+        assert_eq!(decimal, "2,5 h");
+        assert_eq!(clock, "1250:00");
+    }
+}
@@ -0,0 +1,237 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::database::get_hackatime_config_dir;
+use crate::push_log;
+
+pub const DEFAULT_LOCALE: &str = "en";
+pub const AUTO_LOCALE: &str = "auto";
+
+const BUNDLED_CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("de", include_str!("../locales/de.json")),
+];
+
+type Catalog = HashMap<String, String>;
+
+struct ActiveLocale {
+    code: String,
+    messages: Catalog,
+}
+
+static FALLBACK: Lazy<Catalog> = Lazy::new(|| load_catalog(DEFAULT_LOCALE).unwrap_or_default());
+static ACTIVE: Lazy<RwLock<ActiveLocale>> = Lazy::new(|| {
+    RwLock::new(ActiveLocale {
+        code: DEFAULT_LOCALE.to_string(),
+        messages: FALLBACK.clone(),
+    })
+});
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocaleInfo {
+    pub preference: String,
+    pub active: String,
+    pub available: Vec<String>,
+}
+
+fn user_locales_dir() -> Result<PathBuf, String> {
+    Ok(get_hackatime_config_dir()?.join("locales"))
+}
+
+fn parse_catalog(code: &str, content: &str) -> Option<Catalog> {
+    match serde_json::from_str::<Catalog>(content) {
+        Ok(catalog) => Some(catalog),
+        Err(e) => {
+            push_log("warn", "backend", format!("Ignoring invalid message catalog '{}': {}", code, e));
+            None
+        }
+    }
+}
+
+// Entries from config_dir/locales/<code>.json are layered over the bundled catalog,
+// so a user file can tweak a few messages or add a language of its own.
+fn load_catalog(code: &str) -> Option<Catalog> {
+    let bundled = BUNDLED_CATALOGS
+        .iter()
+        .find(|(bundled_code, _)| *bundled_code == code)
+        .and_then(|(_, content)| parse_catalog(code, content));
+
+    let user = user_locales_dir()
+        .ok()
+        .map(|dir| dir.join(format!("{}.json", code)))
+        .filter(|path| path.exists())
+        .and_then(|path| fs::read_to_string(&path).ok())
+        .and_then(|content| parse_catalog(code, &content));
+
+    match (bundled, user) {
+        (Some(mut bundled), Some(user)) => {
+            bundled.extend(user);
+            Some(bundled)
+        }
+        (bundled, user) => bundled.or(user),
+    }
+}
+
+pub fn available_locales() -> Vec<String> {
+    let mut codes: Vec<String> = BUNDLED_CATALOGS.iter().map(|(code, _)| code.to_string()).collect();
+
+    if let Ok(entries) = user_locales_dir().and_then(|dir| fs::read_dir(dir).map_err(|e| e.to_string())) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(code) = path.file_stem().and_then(|stem| stem.to_str()) {
+                if !codes.iter().any(|existing| existing == code) {
+                    codes.push(code.to_string());
+                }
+            }
+        }
+    }
+
+    codes.sort();
+    codes
+}
+
+fn normalize(code: &str) -> String {
+    code.trim().replace('_', "-").split('.').next().unwrap_or_default().to_lowercase()
+}
+
+// "de-AT" falls back to "de", and anything unknown ends up as English.
+fn resolve(preference: &str) -> String {
+    let requested = if preference.is_empty() || preference.eq_ignore_ascii_case(AUTO_LOCALE) {
+        sys_locale::get_locale().unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+    } else {
+        preference.to_string()
+    };
+
+    let requested = normalize(&requested);
+    let available = available_locales();
+    if available.contains(&requested) {
+        return requested;
+    }
+
+    let language = requested.split('-').next().unwrap_or_default().to_string();
+    if available.contains(&language) {
+        language
+    } else {
+        DEFAULT_LOCALE.to_string()
+    }
+}
+
+pub fn is_valid_preference(preference: &str) -> bool {
+    preference.eq_ignore_ascii_case(AUTO_LOCALE) || available_locales().contains(&normalize(preference))
+}
+
+pub fn init(preference: &str) {
+    let code = resolve(preference);
+    let messages = load_catalog(&code).unwrap_or_else(|| FALLBACK.clone());
+
+    if let Ok(mut active) = ACTIVE.write() {
+        active.code = code.clone();
+        active.messages = messages;
+    }
+    push_log("info", "backend", format!("Using locale '{}'", code));
+}
+
+pub fn active_locale() -> String {
+    ACTIVE
+        .read()
+        .map(|active| active.code.clone())
+        .unwrap_or_else(|_| DEFAULT_LOCALE.to_string())
+}
+
+pub fn lookup(key: &str) -> Option<String> {
+    let active = ACTIVE.read().ok()?;
+    active.messages.get(key).or_else(|| FALLBACK.get(key)).cloned()
+}
+
+pub fn t(key: &str) -> String {
+    lookup(key).unwrap_or_else(|| key.to_string())
+}
+
+pub fn t_with(key: &str, args: &[(&str, String)]) -> String {
+    let mut message = t(key);
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+pub fn format_number(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let group_separator = t("number.group_separator");
+    let mut grouped = String::with_capacity(formatted.len() + 4);
+    for (index, digit) in integer.chars().enumerate() {
+        if index > 0 && (integer.len() - index) % 3 == 0 {
+            grouped.push_str(&group_separator);
+        }
+        grouped.push(digit);
+    }
+
+    let mut output = String::new();
+    if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        output.push('-');
+    }
+    output.push_str(&grouped);
+    if let Some(fraction) = fraction {
+        output.push_str(&t("number.decimal_separator"));
+        output.push_str(fraction);
+    }
+    output
+}
+
+pub fn weekday_short(weekday: chrono::Weekday) -> String {
+    t(match weekday {
+        chrono::Weekday::Mon => "weekday.mon",
+        chrono::Weekday::Tue => "weekday.tue",
+        chrono::Weekday::Wed => "weekday.wed",
+        chrono::Weekday::Thu => "weekday.thu",
+        chrono::Weekday::Fri => "weekday.fri",
+        chrono::Weekday::Sat => "weekday.sat",
+        chrono::Weekday::Sun => "weekday.sun",
+    })
+}
+
+#[tauri::command]
+pub fn get_locale() -> Result<LocaleInfo, String> {
+    let preferences = crate::preferences::load_preferences().unwrap_or_default();
+    Ok(LocaleInfo {
+        preference: preferences.locale,
+        active: active_locale(),
+        available: available_locales(),
+    })
+}
+
+#[tauri::command]
+pub fn get_message_catalog() -> Result<HashMap<String, String>, String> {
+    let active = ACTIVE.read().map_err(|e| format!("Failed to read message catalog: {}", e))?;
+    let mut catalog = FALLBACK.clone();
+    catalog.extend(active.messages.clone());
+    Ok(catalog)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_use_the_locale_decimal_separator() {
+        let _guard = crate::test_support::isolated();
+        init("de");
+        let german = (format_number(2.5, 1), format_number(1234.5, 1));
+        init(DEFAULT_LOCALE);
+
+        assert_eq!(german, ("2,5".to_string(), "1.234,5".to_string()));
+        assert_eq!(format_number(2.5, 1), "2.5");
+    }
+}
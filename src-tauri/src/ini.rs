@@ -0,0 +1,420 @@
+// Line-preserving INI document used for ~/.wakatime.cfg and git config files.
+// Unmodified lines are written back byte-for-byte, so comments, ordering,
+// spacing and unknown sections survive an edit.
+
+#[derive(Debug, Clone, PartialEq)]
+enum LineKind {
+    Blank,
+    Comment,
+    Section(String),
+    Entry(String),
+    Continuation,
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    raw: String,
+    kind: LineKind,
+    section: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct IniDocument {
+    lines: Vec<Line>,
+    newline: &'static str,
+    trailing_newline: bool,
+    continuations: bool,
+}
+
+impl IniDocument {
+    pub fn parse(content: &str) -> Self {
+        Self::parse_with(content, true)
+    }
+
+    // Git indents every key inside a section, so leading whitespace there is only
+    // indentation and never continues the previous value.
+    pub fn parse_git_config(content: &str) -> Self {
+        Self::parse_with(content, false)
+    }
+
+    fn parse_with(content: &str, continuations: bool) -> Self {
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        let trailing_newline = content.is_empty() || content.ends_with('\n');
+
+        let mut lines = Vec::new();
+        let mut section = String::new();
+        let mut in_entry = false;
+
+        for raw in content.lines() {
+            let raw = raw.strip_suffix('\r').unwrap_or(raw);
+            let trimmed = raw.trim();
+
+            let kind = if trimmed.is_empty() {
+                in_entry = false;
+                LineKind::Blank
+            } else if trimmed.starts_with('#') || trimmed.starts_with(';') {
+                LineKind::Comment
+            } else if continuations && in_entry && raw.starts_with(|c: char| c.is_whitespace()) {
+                LineKind::Continuation
+            } else if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                in_entry = false;
+                section = trimmed[1..trimmed.len() - 1].trim().to_string();
+                LineKind::Section(section.clone())
+            } else if let Some((key, _, _)) = split_entry(trimmed) {
+                in_entry = true;
+                LineKind::Entry(key.trim().to_string())
+            } else {
+                in_entry = false;
+                LineKind::Other
+            };
+
+            lines.push(Line {
+                raw: raw.to_string(),
+                kind,
+                section: section.clone(),
+            });
+        }
+
+        Self {
+            lines,
+            newline,
+            trailing_newline,
+            continuations,
+        }
+    }
+
+    pub fn set_newline(&mut self, newline: &'static str) {
+        self.newline = newline;
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<String> {
+        let index = self.find_entry(section, key)?;
+        Some(self.entry_value(index))
+    }
+
+    pub fn sections(&self) -> Vec<String> {
+        let mut sections: Vec<String> = Vec::new();
+        for line in &self.lines {
+            if let LineKind::Section(name) = &line.kind {
+                if !sections.contains(name) {
+                    sections.push(name.clone());
+                }
+            }
+        }
+        sections
+    }
+
+    pub fn entries(&self, section: &str) -> Vec<(String, String)> {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| line.section == section)
+            .filter_map(|(index, line)| match &line.kind {
+                LineKind::Entry(key) => Some((key.clone(), self.entry_value(index))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        if let Some(index) = self.find_entry(section, key) {
+            let separator = split_entry(&self.lines[index].raw).map_or('=', |(_, separator, _)| separator);
+            let rendered = render_entry(key, value, separator);
+            let end = self.entry_end(index);
+            self.lines.splice(index..end, rendered.into_iter().map(|raw| Line {
+                kind: LineKind::Other,
+                raw,
+                section: section.to_string(),
+            }));
+            self.reparse();
+            return;
+        }
+
+        let rendered = render_entry(key, value, '=');
+
+        let insert_at = match self.section_insert_point(section) {
+            Some(mut index) => {
+                // Indented stray lines right after the insertion point would otherwise be read
+                // back as continuations of the new entry.
+                while index < self.lines.len()
+                    && self.lines[index].kind == LineKind::Other
+                    && self.lines[index].raw.starts_with(|c: char| c.is_whitespace())
+                {
+                    index += 1;
+                }
+                index
+            }
+            None => {
+                if !section.is_empty() {
+                    if self.lines.last().is_some_and(|line| line.kind != LineKind::Blank) {
+                        self.lines.push(Line {
+                            raw: String::new(),
+                            kind: LineKind::Blank,
+                            section: String::new(),
+                        });
+                    }
+                    self.lines.push(Line {
+                        raw: format!("[{}]", section),
+                        kind: LineKind::Section(section.to_string()),
+                        section: section.to_string(),
+                    });
+                }
+                self.lines.len()
+            }
+        };
+
+        for (offset, raw) in rendered.into_iter().enumerate() {
+            self.lines.insert(insert_at + offset, Line {
+                raw,
+                kind: LineKind::Other,
+                section: section.to_string(),
+            });
+        }
+        self.trailing_newline = true;
+        self.reparse();
+    }
+
+    pub fn remove(&mut self, section: &str, key: &str) -> bool {
+        let mut removed = false;
+        while let Some(index) = self.find_entry(section, key) {
+            let end = self.entry_end(index);
+            self.lines.drain(index..end);
+            removed = true;
+        }
+        removed
+    }
+
+    pub fn clear_section(&mut self, section: &str) {
+        let keys: Vec<String> = self.entries(section).into_iter().map(|(key, _)| key).collect();
+        for key in keys {
+            self.remove(section, &key);
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = self
+            .lines
+            .iter()
+            .map(|line| line.raw.as_str())
+            .collect::<Vec<_>>()
+            .join(self.newline);
+        if self.trailing_newline && !self.lines.is_empty() {
+            output.push_str(self.newline);
+        }
+        output
+    }
+
+    fn find_entry(&self, section: &str, key: &str) -> Option<usize> {
+        self.lines.iter().position(|line| {
+            line.section == section && line.kind == LineKind::Entry(key.to_string())
+        })
+    }
+
+    fn entry_end(&self, index: usize) -> usize {
+        let mut end = index + 1;
+        while end < self.lines.len() && self.lines[end].kind == LineKind::Continuation {
+            end += 1;
+        }
+        end
+    }
+
+    fn entry_value(&self, index: usize) -> String {
+        let first = split_entry(&self.lines[index].raw)
+            .map(|(_, _, value)| value.trim())
+            .unwrap_or("");
+
+        let mut parts: Vec<&str> = Vec::new();
+        if !first.is_empty() {
+            parts.push(first);
+        }
+        for line in &self.lines[index + 1..self.entry_end(index)] {
+            parts.push(line.raw.trim());
+        }
+        parts.join("\n")
+    }
+
+    fn section_insert_point(&self, section: &str) -> Option<usize> {
+        if section.is_empty() {
+            let first_section = self
+                .lines
+                .iter()
+                .position(|line| matches!(line.kind, LineKind::Section(_)))
+                .unwrap_or(self.lines.len());
+            let last_content = self.lines[..first_section]
+                .iter()
+                .rposition(|line| line.kind != LineKind::Blank)
+                .map_or(0, |index| index + 1);
+            return Some(last_content);
+        }
+
+        let header = self
+            .lines
+            .iter()
+            .position(|line| line.kind == LineKind::Section(section.to_string()))?;
+        let mut insert_at = header + 1;
+        for (index, line) in self.lines.iter().enumerate().skip(header + 1) {
+            if matches!(line.kind, LineKind::Section(_)) {
+                break;
+            }
+            if matches!(line.kind, LineKind::Entry(_) | LineKind::Continuation) {
+                insert_at = index + 1;
+            }
+        }
+        Some(insert_at)
+    }
+
+    fn reparse(&mut self) {
+        let trailing_newline = self.trailing_newline;
+        let newline = self.newline;
+        let raw = self
+            .lines
+            .iter()
+            .map(|line| line.raw.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        *self = Self::parse_with(&raw, self.continuations);
+        self.newline = newline;
+        self.trailing_newline = trailing_newline;
+    }
+}
+
+// WakaTime accepts both `key = value` and `key: value`; whichever separator comes
+// first splits the line, so URLs in `key = https://...` values stay intact.
+fn split_entry(line: &str) -> Option<(&str, char, &str)> {
+    let index = line.find(['=', ':'])?;
+    let separator = line[index..].chars().next()?;
+    Some((&line[..index], separator, &line[index + 1..]))
+}
+
+fn render_entry(key: &str, value: &str, separator: char) -> Vec<String> {
+    let prefix = match separator {
+        ':' => format!("{}:", key),
+        _ => format!("{} =", key),
+    };
+    let mut values = value.lines().map(str::trim).filter(|v| !v.is_empty());
+    match value.lines().filter(|v| !v.trim().is_empty()).count() {
+        0 => vec![prefix],
+        1 => vec![format!("{} {}", prefix, values.next().unwrap_or(""))],
+        _ => std::iter::once(prefix)
+            .chain(values.map(|v| format!("    {}", v)))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn line_strategy() -> impl Strategy<Value = String> {
+        prop_oneof![
+            Just(String::new()),
+            "[ \t]{0,3}",
+            "[#;][ a-zA-Z0-9=:._-]{0,20}",
+            "\\[[a-z \"]{1,12}\\]",
+            "[a-z_]{1,10}[ \t]{0,2}[=:][ \t]{0,2}[a-zA-Z0-9=:/._ -]{0,20}",
+            "[ \t]{1,4}[a-zA-Z0-9$^.*/_-]{1,15}",
+            "[a-zA-Z0-9 ._-]{1,20}",
+        ]
+    }
+
+    fn document_strategy() -> impl Strategy<Value = String> {
+        (
+            prop::collection::vec(line_strategy(), 0..30),
+            prop::bool::ANY,
+            prop::bool::ANY,
+        )
+            .prop_map(|(lines, crlf, trailing)| {
+                let newline = if crlf { "\r\n" } else { "\n" };
+                let mut content = lines.join(newline);
+                if trailing && !lines.is_empty() {
+                    content.push_str(newline);
+                }
+                content
+            })
+    }
+
+    fn comments(document: &IniDocument) -> Vec<String> {
+        document
+            .lines
+            .iter()
+            .filter(|line| line.kind == LineKind::Comment)
+            .map(|line| line.raw.clone())
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn parse_then_render_is_byte_identical(content in document_strategy()) {
+            prop_assert_eq!(IniDocument::parse(&content).render(), content);
+        }
+
+        #[test]
+        fn set_keeps_unrelated_keys_and_comments(
+            content in document_strategy(),
+            updates in prop::collection::vec(("[a-z_]{1,6}", "[a-zA-Z0-9:/._-]{1,12}"), 1..5),
+        ) {
+            let original = IniDocument::parse(&content);
+            let mut document = original.clone();
+            for (key, value) in &updates {
+                document.set("settings", key, value);
+            }
+
+            prop_assert_eq!(comments(&document), comments(&original));
+
+            for section in original.sections().into_iter().chain(std::iter::once(String::new())) {
+                for (key, _) in original.entries(&section) {
+                    if section == "settings" && updates.iter().any(|(updated, _)| *updated == key) {
+                        continue;
+                    }
+                    prop_assert_eq!(document.get(&section, &key), original.get(&section, &key));
+                }
+            }
+
+            let reparsed = IniDocument::parse(&document.render());
+            for (key, _) in &updates {
+                let expected = updates.iter().rev().find(|(updated, _)| updated == key).map(|(_, value)| value.clone());
+                prop_assert_eq!(reparsed.get("settings", key), expected);
+                let count_in = |document: &IniDocument| {
+                    document.entries("settings").iter().filter(|(existing, _)| existing == key).count()
+                };
+                prop_assert_eq!(count_in(&reparsed), count_in(&original).max(1));
+            }
+        }
+    }
+
+    #[test]
+    fn colon_separated_entries_are_updated_in_place() {
+        let mut document = IniDocument::parse("[settings]\n# keep me\napi_key: old\nproxy = none\n");
+        assert_eq!(document.get("settings", "api_key").as_deref(), Some("old"));
+
+        document.set("settings", "api_key", "new");
+
+        assert_eq!(document.render(), "[settings]\n# keep me\napi_key: new\nproxy = none\n");
+        assert_eq!(document.entries("settings").len(), 2);
+    }
+
+    #[test]
+    fn equals_before_colon_keeps_urls_in_values() {
+        let document = IniDocument::parse("[settings]\napi_url = https://hackatime.hackclub.com/api/hackatime/v1\n");
+        assert_eq!(
+            document.get("settings", "api_url").as_deref(),
+            Some("https://hackatime.hackclub.com/api/hackatime/v1")
+        );
+    }
+
+    #[test]
+    fn git_config_indentation_is_not_a_continuation() {
+        let content = "[core]\n\trepositoryformatversion = 0\n\tbare = false\n[remote \"origin\"]\n\turl = git@github.com:org/repo.git\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n[branch \"main\"]\n\tremote = origin\n\tmerge = refs/heads/main\n";
+        let document = IniDocument::parse_git_config(content);
+
+        assert_eq!(document.get("remote \"origin\"", "url").as_deref(), Some("git@github.com:org/repo.git"));
+        assert_eq!(
+            document.get("remote \"origin\"", "fetch").as_deref(),
+            Some("+refs/heads/*:refs/remotes/origin/*")
+        );
+        assert_eq!(document.entries("core").len(), 2);
+        assert_eq!(document.render(), content);
+    }
+}
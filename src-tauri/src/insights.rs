@@ -0,0 +1,521 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::database::get_hackatime_config_dir;
+use crate::format::format_hours;
+use crate::i18n::{format_number, lookup};
+use crate::push_log;
+
+const BUNDLED_RULES: &str = include_str!("../insight_rules.json");
+const RULES_FILE_NAME: &str = "insight_rules.json";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Positive,
+    #[default]
+    Neutral,
+    Negative,
+    Info,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Comparison {
+    #[serde(rename = ">")]
+    Greater,
+    #[serde(rename = ">=")]
+    GreaterOrEqual,
+    #[serde(rename = "<")]
+    Less,
+    #[serde(rename = "<=")]
+    LessOrEqual,
+    #[serde(rename = "==")]
+    Equal,
+    #[serde(rename = "!=")]
+    NotEqual,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Condition {
+    pub metric: String,
+    pub op: Comparison,
+    pub value: f64,
+}
+
+// Fields are message templates: `{metric}`, `{metric:.1}` for fixed decimals,
+// `{metric:+}` for a signed whole number and `{metric:duration}` for an hours metric
+// in the preferred duration style are replaced from the stats context.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    pub id: String,
+    pub severity: Severity,
+    pub when: Vec<Condition>,
+    pub title: String,
+    pub description: String,
+    pub value: String,
+    pub trend: String,
+    pub change: String,
+    pub change_type: String,
+    pub period: String,
+    pub icon: String,
+    pub color: String,
+    // Set for rules whose text matches the bundled rule of the same id; only those take
+    // their translations from the message catalog, so edited text is shown as written.
+    #[serde(skip)]
+    pub localized: bool,
+}
+
+// Rules in a group are tried in order and the first match wins, like an if/else chain;
+// a rule without conditions acts as the fallback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleGroup {
+    pub id: String,
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    pub trends: Vec<RuleGroup>,
+    pub insights: Vec<RuleGroup>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StatsContext {
+    pub weekly_hours: f64,
+    pub prev_week_hours: f64,
+    pub weekly_change_percent: f64,
+    pub daily_average_hours: f64,
+    pub daily_average_change_percent: f64,
+    pub current_streak: f64,
+    pub streak_change: f64,
+    pub total_hours: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleMatch {
+    pub id: String,
+    pub severity: Severity,
+    pub title: String,
+    pub description: String,
+    pub value: String,
+    pub trend: String,
+    pub change: String,
+    pub change_type: String,
+    pub period: String,
+    pub icon: String,
+    pub color: String,
+}
+
+fn percent_change(current: f64, previous: f64) -> f64 {
+    if previous > 0.0 {
+        ((current - previous) / previous * 100.0).round()
+    } else if current > 0.0 {
+        100.0
+    } else {
+        0.0
+    }
+}
+
+impl StatsContext {
+    pub fn new(weekly_seconds: f64, prev_week_seconds: f64, all_time_seconds: f64, current_streak: u64) -> Self {
+        let weekly_hours = weekly_seconds / 3600.0;
+        let prev_week_hours = prev_week_seconds / 3600.0;
+        // Without streak history the best guess for last week is one day shorter.
+        let last_week_streak = current_streak.saturating_sub(1);
+
+        Self {
+            weekly_hours,
+            prev_week_hours,
+            weekly_change_percent: percent_change(weekly_hours, prev_week_hours),
+            daily_average_hours: weekly_hours / 7.0,
+            daily_average_change_percent: percent_change(weekly_hours / 7.0, prev_week_hours / 7.0),
+            current_streak: current_streak as f64,
+            streak_change: current_streak as f64 - last_week_streak as f64,
+            total_hours: all_time_seconds / 3600.0,
+        }
+    }
+
+    pub fn metric(&self, name: &str) -> Option<f64> {
+        Some(match name {
+            "weekly_hours" => self.weekly_hours,
+            "prev_week_hours" => self.prev_week_hours,
+            "weekly_change_percent" => self.weekly_change_percent,
+            "daily_average_hours" => self.daily_average_hours,
+            "daily_average_change_percent" => self.daily_average_change_percent,
+            "current_streak" => self.current_streak,
+            "streak_change" => self.streak_change,
+            "total_hours" => self.total_hours,
+            _ => return None,
+        })
+    }
+}
+
+impl Condition {
+    // Unknown metrics never match, so a typo in a user rule file skips the rule
+    // instead of silently treating the metric as zero.
+    fn matches(&self, context: &StatsContext) -> bool {
+        let Some(actual) = context.metric(&self.metric) else {
+            return false;
+        };
+        match self.op {
+            Comparison::Greater => actual > self.value,
+            Comparison::GreaterOrEqual => actual >= self.value,
+            Comparison::Less => actual < self.value,
+            Comparison::LessOrEqual => actual <= self.value,
+            Comparison::Equal => actual == self.value,
+            Comparison::NotEqual => actual != self.value,
+        }
+    }
+}
+
+fn format_metric(value: f64, spec: Option<&str>) -> String {
+    match spec {
+        Some("+") => format!("{:+}", value.round() as i64),
+        Some("duration") => format_hours(value),
+        Some(spec) => match spec.strip_prefix('.').and_then(|digits| digits.parse::<usize>().ok()) {
+            Some(precision) => format_number(value, precision),
+            None => value.to_string(),
+        },
+        None if value.fract() == 0.0 => format_number(value, 0),
+        None => format_number(value, 1),
+    }
+}
+
+pub fn render_template(template: &str, context: &StatsContext) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+
+        let placeholder = &after[..end];
+        let (name, spec) = match placeholder.split_once(':') {
+            Some((name, spec)) => (name.trim(), Some(spec.trim())),
+            None => (placeholder.trim(), None),
+        };
+        match context.metric(name) {
+            Some(value) => output.push_str(&format_metric(value, spec)),
+            None => {
+                output.push('{');
+                output.push_str(placeholder);
+                output.push('}');
+            }
+        }
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+pub fn evaluate(groups: &[RuleGroup], context: &StatsContext) -> Vec<RuleMatch> {
+    groups
+        .iter()
+        .filter_map(|group| {
+            group
+                .rules
+                .iter()
+                .find(|rule| rule.when.iter().all(|condition| condition.matches(context)))
+        })
+        .map(|rule| {
+            // The active message catalog can translate a bundled rule field under
+            // `rules.<rule id>.<field>`; otherwise the text from the rules file is used.
+            let render = |field: &str, template: &str| {
+                let localized = rule
+                    .localized
+                    .then(|| lookup(&format!("rules.{}.{}", rule.id, field)))
+                    .flatten();
+                render_template(localized.as_deref().unwrap_or(template), context)
+            };
+            RuleMatch {
+                id: rule.id.clone(),
+                severity: rule.severity,
+                title: render("title", &rule.title),
+                description: render("description", &rule.description),
+                value: render("value", &rule.value),
+                trend: render("trend", &rule.trend),
+                change: render("change", &rule.change),
+                change_type: rule.change_type.clone(),
+                period: render("period", &rule.period),
+                icon: rule.icon.clone(),
+                color: rule.color.clone(),
+            }
+        })
+        .collect()
+}
+
+fn user_rules_path() -> Result<PathBuf, String> {
+    Ok(get_hackatime_config_dir()?.join(RULES_FILE_NAME))
+}
+
+fn bundled_rules() -> RuleSet {
+    let mut rules: RuleSet = serde_json::from_str(BUNDLED_RULES).unwrap_or_else(|e| {
+        push_log("error", "backend", format!("Bundled insight rules are invalid: {}", e));
+        RuleSet::default()
+    });
+    for rule in rules.trends.iter_mut().chain(rules.insights.iter_mut()).flat_map(|group| group.rules.iter_mut()) {
+        rule.localized = true;
+    }
+    rules
+}
+
+fn same_text(a: &Rule, b: &Rule) -> bool {
+    a.title == b.title
+        && a.description == b.description
+        && a.value == b.value
+        && a.trend == b.trend
+        && a.change == b.change
+        && a.period == b.period
+}
+
+// A user group replaces the bundled group with the same id and new groups are added after
+// the bundled ones, so an override file only needs the groups it changes. An empty `rules`
+// list turns a bundled group off.
+fn merge_groups(bundled: Vec<RuleGroup>, mut user: Vec<RuleGroup>) -> Vec<RuleGroup> {
+    for rule in user.iter_mut().flat_map(|group| group.rules.iter_mut()) {
+        rule.localized = bundled
+            .iter()
+            .flat_map(|group| &group.rules)
+            .any(|original| original.id == rule.id && same_text(original, rule));
+    }
+
+    let mut merged = bundled;
+    for group in user {
+        match merged.iter_mut().find(|existing| existing.id == group.id) {
+            Some(existing) => *existing = group,
+            None => merged.push(group),
+        }
+    }
+    merged
+}
+
+fn merge_rules(bundled: RuleSet, user: RuleSet) -> RuleSet {
+    RuleSet {
+        trends: merge_groups(bundled.trends, user.trends),
+        insights: merge_groups(bundled.insights, user.insights),
+    }
+}
+
+// A broken user file is reported and ignored so the statistics page keeps working.
+pub fn load_rules() -> RuleSet {
+    let Ok(path) = user_rules_path() else {
+        return bundled_rules();
+    };
+    if !path.exists() {
+        return bundled_rules();
+    }
+
+    match fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str::<RuleSet>(&content).map_err(|e| e.to_string()))
+    {
+        Ok(rules) => merge_rules(bundled_rules(), rules),
+        Err(e) => {
+            push_log("warn", "backend", format!("Ignoring invalid insight rules at {}: {}", path.display(), e));
+            bundled_rules()
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_insight_rules() -> Result<RuleSet, String> {
+    Ok(load_rules())
+}
+
+// Copies the bundled rules into the config directory so they can be edited.
+#[tauri::command]
+pub fn create_insight_rules_override() -> Result<String, String> {
+    let path = user_rules_path()?;
+    if path.exists() {
+        return Err(format!("Insight rules already exist at {}", path.display()));
+    }
+
+    fs::write(&path, BUNDLED_RULES).map_err(|e| format!("Failed to write insight rules: {}", e))?;
+    push_log("info", "backend", format!("Created editable insight rules at {}", path.display()));
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> StatsContext {
+        StatsContext::default()
+    }
+
+    fn matched(groups: &[RuleGroup], group: &str, context: &StatsContext) -> String {
+        let group = groups.iter().find(|g| g.id == group).expect("bundled group exists");
+        evaluate(std::slice::from_ref(group), context)
+            .pop()
+            .map(|rule| rule.id)
+            .unwrap_or_default()
+    }
+
+    fn assert_boundaries(
+        groups: &[RuleGroup],
+        group: &str,
+        set: fn(&mut StatsContext, f64),
+        cases: &[(f64, &str)],
+    ) {
+        for (value, expected) in cases {
+            let mut context = context();
+            set(&mut context, *value);
+            assert_eq!(matched(groups, group, &context), *expected, "{} at {}", group, value);
+        }
+    }
+
+    #[test]
+    fn bundled_trends_match_at_their_boundaries() {
+        let _guard = crate::test_support::isolated();
+        let rules = bundled_rules();
+
+        assert_boundaries(&rules.trends, "weekly_time", |c, v| c.weekly_change_percent = v, &[
+            (-1.0, "weekly_time.decrease"),
+            (0.0, "weekly_time.unchanged"),
+            (1.0, "weekly_time.increase"),
+        ]);
+        assert_boundaries(&rules.trends, "streak", |c, v| c.streak_change = v, &[
+            (-1.0, "streak.decrease"),
+            (0.0, "streak.maintained"),
+            (1.0, "streak.increase"),
+        ]);
+        assert_boundaries(&rules.trends, "daily_focus", |c, v| c.daily_average_change_percent = v, &[
+            (-1.0, "daily_focus.decrease"),
+            (0.0, "daily_focus.unchanged"),
+            (1.0, "daily_focus.increase"),
+        ]);
+    }
+
+    #[test]
+    fn bundled_insights_match_at_their_boundaries() {
+        let _guard = crate::test_support::isolated();
+        let rules = bundled_rules();
+
+        assert_boundaries(&rules.insights, "consistency", |c, v| c.daily_average_hours = v, &[
+            (0.0, "consistency.growth"),
+            (0.99, "consistency.growth"),
+            (1.0, "consistency.steady"),
+            (1.99, "consistency.steady"),
+            (2.0, "consistency.consistent"),
+        ]);
+        assert_boundaries(&rules.insights, "streak", |c, v| c.current_streak = v, &[
+            (0.0, "streak.fresh_start"),
+            (1.0, "streak.getting_started"),
+            (6.0, "streak.getting_started"),
+            (7.0, "streak.week_warrior"),
+            (29.0, "streak.week_warrior"),
+            (30.0, "streak.master"),
+        ]);
+        assert_boundaries(&rules.insights, "total_time", |c, v| c.total_hours = v, &[
+            (9.99, "total_time.beginner"),
+            (10.0, "total_time.learning"),
+            (99.99, "total_time.learning"),
+            (100.0, "total_time.experienced"),
+            (999.99, "total_time.experienced"),
+            (1000.0, "total_time.veteran"),
+        ]);
+    }
+
+    #[test]
+    fn placeholders_follow_their_format_spec() {
+        let _guard = crate::test_support::isolated();
+        let context = StatsContext {
+            daily_average_hours: 1.26,
+            streak_change: 3.0,
+            weekly_change_percent: -12.4,
+            current_streak: 5.0,
+            total_hours: 1234.5,
+            ..Default::default()
+        };
+
+        assert_eq!(render_template("{daily_average_hours:.1}h", &context), "1.3h");
+        assert_eq!(render_template("{daily_average_hours:.0}", &context), "1");
+        assert_eq!(render_template("{streak_change:+} days", &context), "+3 days");
+        assert_eq!(render_template("{weekly_change_percent:+}%", &context), "-12%");
+        assert_eq!(render_template("{current_streak}", &context), "5");
+        assert_eq!(render_template("{total_hours}", &context), "1,234.5");
+        assert_eq!(render_template("{total_hours:bogus}", &context), "1234.5");
+        assert_eq!(render_template("{unknown_metric} and {unknown_metric:.1}", &context), "{unknown_metric} and {unknown_metric:.1}");
+        assert_eq!(render_template("{current_streak", &context), "{current_streak");
+    }
+
+    #[test]
+    fn invalid_user_rules_fall_back_to_the_bundled_ones() {
+        let _guard = crate::test_support::isolated();
+        let path = user_rules_path().unwrap();
+        fs::write(&path, "{ \"insights\": [ { \"id\": ").unwrap();
+
+        let rules = load_rules();
+        fs::remove_file(&path).unwrap();
+
+        let ids = |groups: &[RuleGroup]| groups.iter().map(|g| g.id.clone()).collect::<Vec<_>>();
+        let bundled = bundled_rules();
+        assert_eq!(ids(&rules.trends), ids(&bundled.trends));
+        assert_eq!(ids(&rules.insights), ids(&bundled.insights));
+    }
+
+    #[test]
+    fn user_groups_replace_bundled_groups_by_id() {
+        let _guard = crate::test_support::isolated();
+        let path = user_rules_path().unwrap();
+        fs::write(
+            &path,
+            r#"{
+                "insights": [
+                    { "id": "consistency", "rules": [ { "id": "consistency.any", "title": "Any pace is fine" } ] },
+                    { "id": "total_time", "rules": [] },
+                    { "id": "custom", "rules": [ { "id": "custom.hello", "title": "Hello" } ] }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let rules = load_rules();
+        fs::remove_file(&path).unwrap();
+
+        let bundled = bundled_rules();
+        assert_eq!(rules.trends.len(), bundled.trends.len());
+        let ids: Vec<&str> = rules.insights.iter().map(|g| g.id.as_str()).collect();
+        assert_eq!(ids, ["consistency", "streak", "total_time", "custom"]);
+
+        let matches: Vec<String> = evaluate(&rules.insights, &context()).into_iter().map(|m| m.id).collect();
+        assert_eq!(matches, ["consistency.any", "streak.fresh_start", "custom.hello"]);
+    }
+
+    fn with_locale(code: &str, test: impl FnOnce()) {
+        crate::i18n::init(code);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(test));
+        crate::i18n::init(crate::i18n::DEFAULT_LOCALE);
+        if let Err(panic) = result {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    #[test]
+    fn catalog_text_only_replaces_unedited_bundled_rules() {
+        let _guard = crate::test_support::isolated();
+        let path = user_rules_path().unwrap();
+        let mut user = bundled_rules();
+        let consistency = user.insights.iter_mut().find(|g| g.id == "consistency").unwrap();
+        consistency.rules[2].title = "Every minute counts".to_string();
+        fs::write(&path, serde_json::to_string(&user).unwrap()).unwrap();
+
+        let rules = load_rules();
+        fs::remove_file(&path).unwrap();
+
+        with_locale("de", || {
+            let titles: Vec<String> = evaluate(&rules.insights, &context()).into_iter().map(|m| m.title).collect();
+            assert_eq!(titles[0], "Every minute counts");
+            assert_eq!(titles[1], lookup("rules.streak.fresh_start.title").unwrap());
+            assert_ne!(titles[1], bundled_rules().insights[1].rules[3].title);
+        });
+    }
+}
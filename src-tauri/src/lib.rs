@@ -1,22 +1,41 @@
-use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::{Manager, WindowEvent, TitleBarStyle};
-use once_cell::sync::Lazy;
-use std::sync::Mutex;
 use tauri_plugin_deep_link::DeepLinkExt;
 
 
+mod activity;
 mod auth;
+mod auth_flow;
+mod breakdown;
 mod config;
 mod database;
+mod deep_link;
 mod db_commands;
+mod diagnostics;
+mod digest;
 mod discord_rpc;
+mod export;
+mod format;
+mod html;
+mod i18n;
+mod ini;
+mod insights;
+mod logging;
+mod loopback;
+mod plugins;
 mod preferences;
 mod projects;
+mod redaction;
+mod repositories;
+mod report;
 mod session;
 mod setup;
 mod statistics;
+#[cfg(test)]
+mod test_support;
+mod tracking;
 mod tray;
+mod wakatime_settings;
 mod menu;
 mod window;
 
@@ -24,6 +43,7 @@ mod window;
 pub use auth::{AuthState, PkceState};
 pub use config::ApiConfig;
 pub use discord_rpc::{DiscordRpcService};
+pub use logging::push_log;
 pub use session::SessionState;
 
 #[tauri::command]
@@ -41,36 +61,13 @@ fn get_current_os() -> String {
     std::env::consts::OS.to_string()
 }
 
-#[derive(Clone, serde::Serialize)]
-struct LogEntry {
-    ts: i64,
-    level: String,
-    source: String,
-    message: String,
-}
-
-static LOG_BUFFER: Lazy<Mutex<Vec<LogEntry>>> = Lazy::new(|| Mutex::new(Vec::with_capacity(1024)));
-
-pub fn push_log(level: &str, source: &str, message: String) {
-    let mut buf = LOG_BUFFER.lock().unwrap();
-    if buf.len() >= 1000 {
-        buf.remove(0);
-    }
-    buf.push(LogEntry {
-        ts: chrono::Utc::now().timestamp_millis(),
-        level: level.to_string(),
-        source: source.to_string(),
-        message,
-    });
-}
-
-#[tauri::command]
-fn get_recent_logs() -> Vec<LogEntry> {
-    LOG_BUFFER.lock().unwrap().clone()
-}
-
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let preferences = preferences::load_preferences().unwrap_or_default();
+    logging::init(&preferences.log_level);
+    i18n::init(&preferences.locale);
+    format::init(preferences.duration_style);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
             push_log("info", "backend", format!("Single instance detected. Args: {:?}, CWD: {}", args, cwd));
@@ -87,7 +84,7 @@ pub fn run() {
             for arg in args {
                 if arg.starts_with("hackatime://") {
                     push_log("info", "backend", format!("Processing deep link from second instance: {}", arg));
-                    handle_oauth_callback(&app, &arg);
+                    deep_link::handle_deep_link(app, &arg);
                 }
             }
         }))
@@ -95,14 +92,17 @@ pub fn run() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_deep_link::init())
         .manage(ApiConfig::new())
         .manage(Arc::new(tauri::async_runtime::Mutex::new(AuthState {
             is_authenticated: false,
             access_token: None,
             user_info: None,
+            credential_type: auth::CredentialType::OAuth,
         })))
         .manage(Arc::new(tauri::async_runtime::Mutex::new(Option::<PkceState>::None)))
+        .manage(Arc::new(tauri::async_runtime::Mutex::new(auth_flow::AuthFlowState::Idle)))
         .manage(Arc::new(tauri::async_runtime::Mutex::new(DiscordRpcService::new())))
         .manage(Arc::new(tauri::async_runtime::Mutex::new(SessionState {
             is_active: false,
@@ -113,12 +113,16 @@ pub fn run() {
             editor: None,
             language: None,
             entity: None,
+            repository: None,
         })))
         .invoke_handler(tauri::generate_handler![
             greet,
             get_app_version,
             get_current_os,
-            get_recent_logs,
+            logging::get_recent_logs,
+            logging::subscribe_logs,
+            logging::unsubscribe_logs,
+            logging::export_logs,
             
             database::get_platform_info,
             
@@ -129,6 +133,8 @@ pub fn run() {
             auth::authenticate_with_rails,
             auth::handle_auth_callback,
             auth::handle_deep_link_callback,
+            auth::login_with_api_key,
+            auth_flow::get_auth_flow_state,
             auth::logout,
             auth::test_auth_callback,
             auth::authenticate_with_direct_oauth,
@@ -144,6 +150,17 @@ pub fn run() {
             preferences::get_notifications_enabled,
             preferences::set_discord_rpc_enabled,
             preferences::get_discord_rpc_enabled,
+            preferences::set_tray_time_display_enabled,
+            preferences::get_tray_time_display_enabled,
+            preferences::set_daily_goal_hours,
+            preferences::get_daily_goal_hours,
+            preferences::set_log_level,
+            preferences::get_log_level,
+            preferences::set_locale,
+            preferences::set_duration_style,
+            preferences::get_duration_style,
+            i18n::get_locale,
+            i18n::get_message_catalog,
             
             setup::setup_hackatime_macos_linux,
             setup::setup_hackatime_windows,
@@ -152,16 +169,33 @@ pub fn run() {
             setup::check_wakatime_config,
             setup::apply_wakatime_config,
             
+            wakatime_settings::get_wakatime_settings,
+            wakatime_settings::set_wakatime_settings,
+            wakatime_settings::validate_wakatime_settings,
+            
+            plugins::get_editor_plugin_report,
+            
+            tracking::pause_tracking,
+            tracking::resume_tracking,
+            tracking::get_tracking_status,
+            
             session::get_latest_heartbeat,
             session::get_current_session,
             session::get_app_status,
             
             db_commands::get_hackatime_directories,
             db_commands::cleanup_old_sessions,
+            db_commands::list_sessions,
+            db_commands::inspect_session,
+            db_commands::revoke_session,
+            db_commands::remove_all_accounts,
             db_commands::get_session_stats,
             db_commands::test_database_connection,
             db_commands::clear_statistics_cache,
             
+            diagnostics::run_diagnostics,
+            diagnostics::export_diagnostics_report,
+            
             discord_rpc::discord_rpc_connect,
             discord_rpc::discord_rpc_disconnect,
             discord_rpc::discord_rpc_set_activity,
@@ -173,6 +207,26 @@ pub fn run() {
             
             projects::get_projects,
             projects::get_project_details,
+            projects::search_projects,
+            projects::get_project_metadata,
+            projects::set_project_metadata,
+            projects::list_project_tags,
+            projects::get_tag_stats,
+            breakdown::get_project_files,
+            breakdown::get_project_branches,
+            export::preview_timesheet,
+            export::export_timesheet,
+            report::generate_report,
+            report::list_report_templates,
+            report::create_report_template,
+            digest::get_weekly_digests,
+            digest::generate_weekly_digest,
+            digest::export_weekly_digest,
+            insights::get_insight_rules,
+            insights::create_insight_rules_override,
+            repositories::list_repositories,
+            repositories::open_repository_folder,
+            repositories::open_repository_remote,
             
             statistics::get_statistics_data,
             statistics::get_dashboard_stats,
@@ -182,6 +236,7 @@ pub fn run() {
             window::toggle_window,
         ])
         .setup(|app| {
+            logging::attach_app_handle(app.handle());
             push_log("info", "backend", "backend starting".to_string());
             
             if let Some(window) = app.get_webview_window("main") {
@@ -235,13 +290,17 @@ pub fn run() {
 
             
             if let Err(e) = menu::setup_app_menu(&app.handle()) {
-                eprintln!("Failed to setup app menu: {}", e);
+                log::error!("Failed to setup app menu: {}", e);
             }
 
             
             if let Err(e) = tray::setup_tray(&app.handle()) {
-                eprintln!("Failed to setup tray: {}", e);
+                log::error!("Failed to setup tray: {}", e);
             }
+            tray::start_tray_status_updater(app.handle());
+            db_commands::start_session_cleanup();
+            digest::start_weekly_digest_scheduler(app.handle());
+            tracking::restore_tracking_pause(app.handle());
 
             
             let auth_state = app.state::<Arc<tauri::async_runtime::Mutex<AuthState>>>();
@@ -301,7 +360,8 @@ pub fn run() {
             #[cfg(any(target_os = "linux", target_os = "windows"))]
             {
                 app.deep_link().register_all().unwrap_or_else(|e| {
-                push_log("error", "backend", format!("Failed to register deep links: {}", e));
+                push_log("error", "backend", format!("Failed to register deep links, falling back to loopback login: {}", e));
+                deep_link::mark_registration_failed();
                 });
             }
 
@@ -354,10 +414,7 @@ fn setup_deep_link_handlers(app: &mut tauri::App) {
         push_log("info", "backend", format!("Deep link received: {:?}", urls));
         
         for url in urls {
-            let url_string = url.to_string();
-            if url_string.starts_with("hackatime://auth/callback") {
-                handle_oauth_callback(&app_handle, &url_string);
-            }
+            deep_link::handle_deep_link(&app_handle, url.as_str());
         }
     });
 
@@ -366,162 +423,7 @@ fn setup_deep_link_handlers(app: &mut tauri::App) {
         push_log("info", "backend", format!("App started with deep link: {:?}", start_urls));
         let app_handle = app.handle().clone();
         for url in start_urls {
-            let url_string = url.to_string();
-            if url_string.starts_with("hackatime://auth/callback") {
-                handle_oauth_callback(&app_handle, &url_string);
-            }
-        }
-    }
-}
-
-fn handle_oauth_callback(app_handle: &tauri::AppHandle, url_string: &str) {
-    if let Some(query_start) = url_string.find('?') {
-        let query = &url_string[query_start + 1..];
-        let params: Vec<&str> = query.split('&').collect();
-        
-        let mut code = None;
-        let mut state = None;
-        let mut error = None;
-        
-        for param in params {
-            if param.starts_with("code=") {
-                code = Some(param[5..].to_string());
-            } else if param.starts_with("state=") {
-                state = Some(param[6..].to_string());
-            } else if param.starts_with("error=") {
-                error = Some(param[6..].to_string());
-            }
-        }
-        
-        if let Some(error) = error {
-            push_log("error", "backend", format!("OAuth error: {}", error));
-            return;
-        }
-        
-        if let (Some(code), Some(state)) = (code, state) {
-            push_log("info", "backend", format!("Extracted authorization code: {} and state: {}", code, state));
-            
-            let api_config = app_handle.state::<ApiConfig>();
-            let auth_state = app_handle.state::<Arc<tauri::async_runtime::Mutex<AuthState>>>();
-            let pkce_state = app_handle.state::<Arc<tauri::async_runtime::Mutex<Option<PkceState>>>>();
-            
-            let code_clone = code.clone();
-            let state_clone = state.clone();
-            let api_config_clone = api_config.inner().clone();
-            let auth_state_clone = auth_state.inner().clone();
-            let pkce_state_clone = pkce_state.inner().clone();
-            
-            tauri::async_runtime::spawn(async move {
-                process_oauth_token_exchange(
-                    code_clone,
-                    state_clone,
-                    api_config_clone,
-                    auth_state_clone,
-                    pkce_state_clone,
-                ).await;
-            });
-        } else {
-            push_log("warn", "backend", "Missing code or state parameter in OAuth callback".to_string());
-        }
-    }
-}
-
-async fn process_oauth_token_exchange(
-    code: String,
-    state: String,
-    api_config: ApiConfig,
-    auth_state: Arc<tauri::async_runtime::Mutex<AuthState>>,
-    pkce_state: Arc<tauri::async_runtime::Mutex<Option<PkceState>>>,
-) {
-    let client = reqwest::Client::new();
-    
-    let stored_pkce = {
-        let pkce_guard = pkce_state.lock().await;
-        pkce_guard.clone()
-    };
-
-    let pkce = match stored_pkce {
-        Some(pkce) => {
-            if pkce.is_expired(600) {
-                eprintln!("PKCE state expired. Please restart authentication.");
-                return;
-            }
-            
-            if pkce.state != state {
-                eprintln!("State parameter mismatch. Possible CSRF attack.");
-                return;
-            }
-            
-            pkce
-        }
-        None => {
-            eprintln!("No PKCE state found. Please restart authentication.");
-            return;
-        }
-    };
-
-    let response = client
-        .post(&format!("{}/oauth/token", api_config.base_url))
-        .form(&[
-            ("grant_type", "authorization_code"),
-            ("code", &code),
-            ("client_id", "BPr5VekIV-xuQ2ZhmxbGaahJ3XVd7gM83pql-HYGYxQ"),
-            ("redirect_uri", "hackatime://auth/callback"),
-            ("code_verifier", &pkce.code_verifier),
-        ])
-        .send()
-        .await;
-
-    match response {
-        Ok(resp) => {
-            if resp.status().is_success() {
-                if let Ok(token_response) = resp.json::<serde_json::Value>().await {
-                    if let Some(access_token) = token_response["access_token"].as_str() {
-                        let user_response = client
-                            .get(&format!("{}/api/v1/authenticated/me", api_config.base_url))
-                            .bearer_auth(access_token)
-                            .send()
-                            .await;
-
-                        let user_info = match user_response {
-                            Ok(resp) if resp.status().is_success() => {
-                                resp.json::<serde_json::Value>().await.unwrap_or_else(|_| serde_json::json!({}))
-                            }
-                            _ => serde_json::json!({})
-                        };
-
-                        let mut user_info_map = HashMap::new();
-                        if let Some(obj) = user_info.as_object() {
-                            for (key, value) in obj {
-                                user_info_map.insert(key.clone(), value.clone());
-                            }
-                        }
-
-                        let mut auth_state = auth_state.lock().await;
-                        auth_state.is_authenticated = true;
-                        auth_state.access_token = Some(access_token.to_string());
-                        auth_state.user_info = Some(user_info_map);
-
-                        let auth_state_to_save = auth_state.clone();
-                        drop(auth_state);
-                        if let Err(e) = auth::save_auth_state(auth_state_to_save).await {
-                            eprintln!("Failed to save auth state: {}", e);
-                        }
-
-                        {
-                            let mut stored_pkce = pkce_state.lock().await;
-                            *stored_pkce = None;
-                        }
-
-                        println!("OAuth authentication successful!");
-                    }
-                }
-            } else {
-                eprintln!("Token exchange failed with status: {}", resp.status());
-            }
-        }
-        Err(e) => {
-            eprintln!("Failed to exchange token: {}", e);
+            deep_link::handle_deep_link(&app_handle, url.as_str());
         }
     }
 }
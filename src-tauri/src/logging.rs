@@ -0,0 +1,517 @@
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::get_hackatime_logs_dir;
+use crate::redaction::redact;
+
+const LOG_BUFFER_CAPACITY: usize = 1000;
+const LOG_FILE_NAME: &str = "hackatime.log";
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_LOG_FILES: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub ts: i64,
+    pub level: String,
+    pub source: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fields: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFileError {
+    pub ts: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogFilter {
+    #[serde(default)]
+    pub levels: Option<Vec<String>>,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub since: Option<i64>,
+    #[serde(default)]
+    pub until: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl LogFilter {
+    fn matches(&self, entry: &LogEntry) -> bool {
+        if let Some(levels) = &self.levels {
+            if !levels.iter().any(|level| level.eq_ignore_ascii_case(&entry.level)) {
+                return false;
+            }
+        }
+        if let Some(sources) = &self.sources {
+            if !sources.iter().any(|source| source == &entry.source) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| entry.ts < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| entry.ts > until) {
+            return false;
+        }
+        true
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogStreamEvent {
+    pub subscription_id: u64,
+    pub entry: LogEntry,
+}
+
+static LOG_BUFFER: Lazy<Mutex<VecDeque<LogEntry>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY)));
+static LOG_FILE: Lazy<Mutex<Option<RotatingLogFile>>> = Lazy::new(|| Mutex::new(None));
+static LOG_SUBSCRIPTIONS: Lazy<Mutex<HashMap<u64, LogFilter>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_SUBSCRIPTION_ID: AtomicU64 = AtomicU64::new(1);
+static LOG_FILE_ERROR: Lazy<Mutex<Option<LogFileError>>> = Lazy::new(|| Mutex::new(None));
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+static LOGGER: HackatimeLogger = HackatimeLogger;
+
+struct RotatingLogFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+    opened_on: chrono::NaiveDate,
+}
+
+impl RotatingLogFile {
+    fn open(dir: &Path) -> std::io::Result<Self> {
+        let path = dir.join(LOG_FILE_NAME);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        let opened_on = metadata
+            .modified()
+            .map(|modified| chrono::DateTime::<chrono::Local>::from(modified).date_naive())
+            .unwrap_or_else(|_| chrono::Local::now().date_naive());
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file,
+            size: metadata.len(),
+            opened_on,
+        })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let today = chrono::Local::now().date_naive();
+        if self.size > 0 && (self.size + line.len() as u64 > MAX_LOG_FILE_BYTES || self.opened_on != today) {
+            self.rotate()?;
+        }
+
+        self.file.write_all(line.as_bytes())?;
+        self.file.write_all(b"\n")?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.file.flush()?;
+        let current = self.dir.join(LOG_FILE_NAME);
+        // `rename` replaces an existing target, so archives from the same instant get a counter.
+        let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+        let mut rotated = self.dir.join(format!("hackatime-{}.log", timestamp));
+        let mut attempt = 1;
+        while rotated.exists() {
+            rotated = self.dir.join(format!("hackatime-{}-{:03}.log", timestamp, attempt));
+            attempt += 1;
+        }
+        fs::rename(&current, &rotated)?;
+
+        *self = Self::open(&self.dir)?;
+        self.opened_on = chrono::Local::now().date_naive();
+        prune_rotated_logs(&self.dir);
+        Ok(())
+    }
+}
+
+fn prune_rotated_logs(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut rotated: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| {
+                    let name = name.to_string_lossy();
+                    name.starts_with("hackatime-") && name.ends_with(".log")
+                })
+                .unwrap_or(false)
+        })
+        .collect();
+
+    rotated.sort();
+    if rotated.len() > MAX_ROTATED_LOG_FILES {
+        for old in &rotated[..rotated.len() - MAX_ROTATED_LOG_FILES] {
+            let _ = fs::remove_file(old);
+        }
+    }
+}
+
+struct FieldCollector(BTreeMap<String, String>);
+
+impl<'kvs> log::kv::VisitSource<'kvs> for FieldCollector {
+    fn visit_pair(&mut self, key: log::kv::Key<'kvs>, value: log::kv::Value<'kvs>) -> Result<(), log::kv::Error> {
+        self.0.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+}
+
+struct HackatimeLogger;
+
+impl HackatimeLogger {
+    // Our own records use a bare source ("backend", "frontend") or the crate's module path;
+    // everything else comes from dependencies and is only interesting when it goes wrong.
+    fn is_own_target(target: &str) -> bool {
+        !target.contains("::") || target.starts_with("desktop_lib")
+    }
+}
+
+impl log::Log for HackatimeLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        if Self::is_own_target(metadata.target()) {
+            metadata.level() <= log::max_level()
+        } else {
+            metadata.level() <= log::Level::Warn
+        }
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut fields = FieldCollector(BTreeMap::new());
+        let _ = record.key_values().visit(&mut fields);
+        for value in fields.0.values_mut() {
+            *value = redact(value);
+        }
+
+        let source = if record.target().starts_with("desktop_lib") {
+            "backend".to_string()
+        } else {
+            record.target().to_string()
+        };
+
+        let entry = LogEntry {
+            ts: chrono::Utc::now().timestamp_millis(),
+            level: record.level().as_str().to_lowercase(),
+            source,
+            message: redact(&record.args().to_string()),
+            fields: fields.0,
+        };
+
+        write_to_file(&entry);
+        stream_to_subscribers(&entry);
+
+        let mut buf = LOG_BUFFER.lock().unwrap();
+        if buf.len() >= LOG_BUFFER_CAPACITY {
+            buf.pop_front();
+        }
+        buf.push_back(entry);
+    }
+
+    fn flush(&self) {
+        if let Ok(mut guard) = LOG_FILE.lock() {
+            if let Some(log_file) = guard.as_mut() {
+                let _ = log_file.file.flush();
+            }
+        }
+    }
+}
+
+fn write_to_file(entry: &LogEntry) {
+    let Ok(line) = serde_json::to_string(entry) else {
+        return;
+    };
+    let Ok(mut guard) = LOG_FILE.lock() else {
+        return;
+    };
+
+    if guard.is_none() {
+        let dir = match get_hackatime_logs_dir() {
+            Ok(dir) => dir,
+            Err(e) => return set_log_file_error(e),
+        };
+        match RotatingLogFile::open(&dir) {
+            Ok(log_file) => *guard = Some(log_file),
+            Err(e) => return set_log_file_error(format!("Failed to open log file in {}: {}", dir.display(), e)),
+        }
+    }
+
+    if let Some(log_file) = guard.as_mut() {
+        match log_file.write_line(&line) {
+            Ok(()) => {
+                if let Ok(mut error) = LOG_FILE_ERROR.lock() {
+                    *error = None;
+                }
+            }
+            Err(e) => {
+                // Reopen on the next record rather than stop logging to disk for the session.
+                *guard = None;
+                set_log_file_error(format!("Failed to write log file: {}", e));
+            }
+        }
+    }
+}
+
+// Failures can't be logged through the logger that is failing, so the latest one is kept
+// for diagnostics to report instead.
+fn set_log_file_error(message: String) {
+    if let Ok(mut error) = LOG_FILE_ERROR.lock() {
+        *error = Some(LogFileError {
+            ts: chrono::Utc::now().timestamp_millis(),
+            message,
+        });
+    }
+}
+
+pub fn last_log_file_error() -> Option<LogFileError> {
+    LOG_FILE_ERROR.lock().ok().and_then(|error| error.clone())
+}
+
+fn stream_to_subscribers(entry: &LogEntry) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+
+    // Collect first and emit outside the lock: emitting can itself log, which would re-enter here.
+    let subscribers: Vec<u64> = match LOG_SUBSCRIPTIONS.lock() {
+        Ok(subscriptions) => subscriptions
+            .iter()
+            .filter(|(_, filter)| filter.matches(entry))
+            .map(|(id, _)| *id)
+            .collect(),
+        Err(_) => return,
+    };
+
+    for subscription_id in subscribers {
+        let _ = app.emit(
+            &format!("log-stream:{}", subscription_id),
+            LogStreamEvent {
+                subscription_id,
+                entry: entry.clone(),
+            },
+        );
+    }
+}
+
+pub fn parse_level(level: &str) -> Option<log::LevelFilter> {
+    match level.to_lowercase().as_str() {
+        "trace" => Some(log::LevelFilter::Trace),
+        "debug" => Some(log::LevelFilter::Debug),
+        "info" => Some(log::LevelFilter::Info),
+        "warn" | "warning" => Some(log::LevelFilter::Warn),
+        "error" => Some(log::LevelFilter::Error),
+        "off" => Some(log::LevelFilter::Off),
+        _ => None,
+    }
+}
+
+pub fn init(level: &str) {
+    if log::set_logger(&LOGGER).is_err() {
+        return;
+    }
+    log::set_max_level(parse_level(level).unwrap_or(log::LevelFilter::Info));
+}
+
+pub fn attach_app_handle(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+pub fn set_level(level: log::LevelFilter) {
+    log::set_max_level(level);
+}
+
+pub fn push_log(level: &str, source: &str, message: String) {
+    let level = match level {
+        "error" => log::Level::Error,
+        "warn" => log::Level::Warn,
+        "debug" => log::Level::Debug,
+        "trace" => log::Level::Trace,
+        _ => log::Level::Info,
+    };
+    log::log!(target: source, level, "{}", message);
+}
+
+#[tauri::command]
+pub fn get_recent_logs(filter: Option<LogFilter>) -> Vec<LogEntry> {
+    let filter = filter.unwrap_or_default();
+    let buf = LOG_BUFFER.lock().unwrap();
+    let mut entries: Vec<LogEntry> = buf.iter().filter(|entry| filter.matches(entry)).cloned().collect();
+
+    if let Some(limit) = filter.limit {
+        if entries.len() > limit {
+            entries.drain(..entries.len() - limit);
+        }
+    }
+    entries
+}
+
+#[tauri::command]
+pub fn subscribe_logs(filter: Option<LogFilter>) -> u64 {
+    let id = NEXT_SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed);
+    LOG_SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .insert(id, filter.unwrap_or_default());
+    id
+}
+
+#[tauri::command]
+pub fn unsubscribe_logs(subscription_id: u64) -> bool {
+    LOG_SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .remove(&subscription_id)
+        .is_some()
+}
+
+#[tauri::command]
+pub fn export_logs(app: tauri::AppHandle, path: String) -> Result<String, String> {
+    log::logger().flush();
+
+    let logs_dir = get_hackatime_logs_dir()?;
+    let file = File::create(&path)
+        .map_err(|e| format!("Failed to create log bundle: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let info = serde_json::json!({
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "log_level": log::max_level().as_str().to_lowercase(),
+    });
+    zip.start_file("info.json", options)
+        .and_then(|_| {
+            zip.write_all(serde_json::to_string_pretty(&info).unwrap_or_default().as_bytes())
+                .map_err(Into::into)
+        })
+        .map_err(|e| format!("Failed to write log bundle: {}", e))?;
+
+    let mut log_files: Vec<PathBuf> = fs::read_dir(&logs_dir)
+        .map_err(|e| format!("Failed to read logs directory: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|ext| ext == "log").unwrap_or(false))
+        .collect();
+    log_files.sort();
+
+    for log_path in &log_files {
+        let name = log_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let contents = fs::read(log_path)
+            .map_err(|e| format!("Failed to read {}: {}", log_path.display(), e))?;
+        zip.start_file(format!("logs/{}", name), options)
+            .and_then(|_| zip.write_all(&contents).map_err(Into::into))
+            .map_err(|e| format!("Failed to write log bundle: {}", e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish log bundle: {}", e))?;
+
+    log::info!(target: "backend", files = log_files.len(); "Exported logs to {}", path);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_files_content(dir: &Path) -> String {
+        let mut content = String::new();
+        for entry in fs::read_dir(dir).unwrap().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with("hackatime") && name.ends_with(".log") {
+                content.push_str(&fs::read_to_string(entry.path()).unwrap());
+            }
+        }
+        content
+    }
+
+    #[test]
+    fn registered_secrets_never_reach_the_buffer_or_log_files() {
+        let _guard = crate::test_support::isolated();
+        init("trace");
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let secret = "oauth-access-token-4f1c2d";
+        let api_key = "waka_123e4567-e89b-12d3-a456-426614174000";
+        crate::redaction::register_secret(secret);
+
+        log::info!("Exchanged code for {}", secret);
+        push_log("debug", "backend", format!("Authorization: Bearer {}", secret));
+        log::warn!(target: "auth", token = secret; "Retrying with {}", api_key);
+
+        // Rotate so the records end up in an archived file as well as the live one.
+        LOG_FILE.lock().unwrap().as_mut().expect("log file should be open").rotate().unwrap();
+        log::info!("After rotation {}", secret);
+        log::logger().flush();
+
+        let buffered = serde_json::to_string(&get_recent_logs(None)).unwrap();
+        assert!(buffered.contains("Exchanged code for"));
+        assert!(!buffered.contains(secret));
+        assert!(!buffered.contains(api_key));
+
+        let dir = get_hackatime_logs_dir().unwrap();
+        let files = log_files_content(&dir);
+        assert!(files.contains("Exchanged code for"));
+        assert!(files.contains("After rotation"));
+        assert!(!files.contains(secret));
+        assert!(!files.contains(api_key));
+
+        let rotated = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("hackatime-"))
+            .count();
+        assert!(rotated >= 1);
+    }
+
+    #[test]
+    fn rotations_in_the_same_instant_keep_every_archive() {
+        let _guard = crate::test_support::isolated();
+        let dir = std::env::temp_dir().join(format!("hackatime-rotation-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut file = RotatingLogFile::open(&dir).unwrap();
+        for i in 0..3 {
+            file.write_line(&format!("line {}", i)).unwrap();
+            file.rotate().unwrap();
+        }
+
+        let mut archived: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("hackatime-"))
+            .map(|entry| fs::read_to_string(entry.path()).unwrap())
+            .collect();
+        archived.sort();
+        assert_eq!(archived, vec!["line 0\n", "line 1\n", "line 2\n"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,214 @@
+use std::time::Duration;
+use tauri::Url;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::auth_flow::{AuthFlow, PKCE_MAX_AGE_SECONDS};
+use crate::html::escape_html;
+use crate::push_log;
+
+const CALLBACK_PATH: &str = "/callback";
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+const PAGE_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Hackatime</title>
+<style>
+  body { font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; background: #17171d; color: #f9fafc; display: flex; align-items: center; justify-content: center; height: 100vh; margin: 0; }
+  main { text-align: center; max-width: 420px; padding: 24px; }
+  h1 { color: {{color}}; font-size: 24px; }
+  p { color: #a0a0b0; line-height: 1.5; }
+</style>
+</head>
+<body>
+<main>
+  <h1>{{title}}</h1>
+  <p>{{message}}</p>
+</main>
+</body>
+</html>"#;
+
+fn render_page(result: &Result<(), String>) -> String {
+    let (color, title, message) = match result {
+        Ok(()) => (
+            "#33d6a6",
+            "You're logged in!",
+            "Hackatime Desktop is now connected. You can close this tab and return to the app.".to_string(),
+        ),
+        Err(e) => ("#ec3750", "Login failed", format!("{} Please return to the app and try again.", e)),
+    };
+
+    PAGE_TEMPLATE
+        .replace("{{color}}", color)
+        .replace("{{title}}", title)
+        .replace("{{message}}", &escape_html(&message))
+}
+
+async fn read_request_target(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+
+    let read = async {
+        while !buffer.windows(4).any(|w| w == b"\r\n\r\n") && buffer.len() < MAX_REQUEST_BYTES {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+    };
+    tokio::time::timeout(REQUEST_READ_TIMEOUT, read).await.ok()?;
+
+    let request = String::from_utf8_lossy(&buffer);
+    let mut parts = request.lines().next()?.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, status: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn serve_callback(listener: TcpListener, flow: AuthFlow) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(PKCE_MAX_AGE_SECONDS as u64);
+
+    loop {
+        let mut stream = match tokio::time::timeout_at(deadline, listener.accept()).await {
+            Ok(Ok((stream, _))) => stream,
+            Ok(Err(e)) => {
+                push_log("warn", "backend", format!("Loopback login accept failed: {}", e));
+                continue;
+            }
+            Err(_) => {
+                push_log("info", "backend", "Loopback login listener timed out".to_string());
+                return;
+            }
+        };
+
+        let Some(target) = read_request_target(&mut stream).await else {
+            write_response(&mut stream, "400 Bad Request", "").await;
+            continue;
+        };
+        let Ok(url) = Url::parse(&format!("http://127.0.0.1{}", target)) else {
+            write_response(&mut stream, "400 Bad Request", "").await;
+            continue;
+        };
+        // Browsers also ask for /favicon.ico and the like; only the callback ends the login.
+        if url.path() != CALLBACK_PATH {
+            write_response(&mut stream, "404 Not Found", "").await;
+            continue;
+        }
+
+        let param = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.into_owned())
+                .filter(|v| !v.is_empty())
+        };
+
+        // Anything on this machine can hit the port, so a callback for another login must not
+        // end this one; keep waiting for the matching state until the deadline.
+        let state = param("state");
+        if flow.is_foreign_state(state.as_deref()).await {
+            push_log("warn", "backend", "Ignoring loopback callback with mismatched state".to_string());
+            let rejected = Err("State parameter mismatch.".to_string());
+            write_response(&mut stream, "400 Bad Request", &render_page(&rejected)).await;
+            continue;
+        }
+
+        push_log("info", "backend", "Received OAuth callback on loopback listener".to_string());
+        let result = flow
+            .handle_callback(param("code"), state, param("error_description").or_else(|| param("error")))
+            .await;
+
+        write_response(&mut stream, "200 OK", &render_page(&result)).await;
+        return;
+    }
+}
+
+pub async fn begin_loopback_login(flow: AuthFlow) -> Result<String, String> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| format!("Failed to start loopback login listener: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read loopback listener address: {}", e))?
+        .port();
+
+    let redirect_uri = format!("http://127.0.0.1:{}{}", port, CALLBACK_PATH);
+    let auth_url = flow.begin(&redirect_uri).await;
+    push_log("info", "backend", format!("Listening for OAuth callback on {}", redirect_uri));
+
+    tauri::async_runtime::spawn(serve_callback(listener, flow));
+    Ok(auth_url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::{AuthState, CredentialType};
+    use crate::auth_flow::AuthFlowState;
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn mismatched_state_keeps_the_listener_waiting() {
+        let _guard = crate::test_support::isolated();
+        tokio::runtime::Runtime::new().unwrap().block_on(async {
+            let server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .and(path("/oauth/token"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "access_token": "loopback-token" })))
+                .expect(1)
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path("/api/v1/authenticated/me"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "id": 42 })))
+                .mount(&server)
+                .await;
+
+            let auth_state = Arc::new(tauri::async_runtime::Mutex::new(AuthState {
+                is_authenticated: false,
+                access_token: None,
+                user_info: None,
+                credential_type: CredentialType::default(),
+            }));
+            let flow_state = Arc::new(tauri::async_runtime::Mutex::new(AuthFlowState::Idle));
+            let flow = AuthFlow::with_state(
+                server.uri(),
+                reqwest::Client::new(),
+                auth_state.clone(),
+                Arc::new(tauri::async_runtime::Mutex::new(None)),
+                flow_state.clone(),
+            );
+            let auth_url = Url::parse(&begin_loopback_login(flow).await.unwrap()).unwrap();
+            let query = |key: &str| auth_url.query_pairs().find(|(k, _)| k == key).unwrap().1.into_owned();
+            let redirect_uri = query("redirect_uri");
+
+            let forged = reqwest::get(format!("{}?code=forged&state=forged", redirect_uri)).await.unwrap();
+            assert_eq!(forged.status(), 400);
+            assert!(!matches!(*flow_state.lock().await, AuthFlowState::Failed { .. }));
+
+            let genuine = reqwest::get(format!("{}?code=code&state={}", redirect_uri, query("state"))).await.unwrap();
+            assert_eq!(genuine.status(), 200);
+            assert!(genuine.text().await.unwrap().contains("You're logged in!"));
+            assert!(auth_state.lock().await.is_authenticated);
+
+            let db = crate::database::Database::new().await.unwrap();
+            db.delete_session_for_account("user:42").await.unwrap();
+        });
+    }
+}
@@ -1,13 +1,14 @@
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Manager, Wry};
 use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use crate::i18n::t;
 use crate::push_log;
 
-pub fn setup_app_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+fn build_app_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
     
-    let quit_item = PredefinedMenuItem::quit(app, Some("Quit Hackatime"))?;
+    let quit_item = PredefinedMenuItem::quit(app, Some(&t("menu.quit")))?;
     let about_menu = Submenu::with_items(
         app,
-        "Hackatime",
+        t("menu.app"),
         true,
         &[
             &quit_item,
@@ -15,11 +16,11 @@ pub fn setup_app_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
     )?;
 
     
-    let file_new = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
-    let file_hide = MenuItem::with_id(app, "hide", "Hide Window", true, None::<&str>)?;
+    let file_new = MenuItem::with_id(app, "show", t("menu.show_window"), true, None::<&str>)?;
+    let file_hide = MenuItem::with_id(app, "hide", t("menu.hide_window"), true, None::<&str>)?;
     let file_menu = Submenu::with_items(
         app,
-        "File",
+        t("menu.file"),
         true,
         &[
             &file_new,
@@ -28,15 +29,33 @@ pub fn setup_app_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
     )?;
 
     
-    let edit_undo = PredefinedMenuItem::undo(app, Some("Undo"))?;
-    let edit_redo = PredefinedMenuItem::redo(app, Some("Redo"))?;
-    let edit_cut = PredefinedMenuItem::cut(app, Some("Cut"))?;
-    let edit_copy = PredefinedMenuItem::copy(app, Some("Copy"))?;
-    let edit_paste = PredefinedMenuItem::paste(app, Some("Paste"))?;
-    let edit_select_all = PredefinedMenuItem::select_all(app, Some("Select All"))?;
+    let tracking_pause_15 = MenuItem::with_id(app, "tracking_pause_15", t("menu.pause_15"), true, None::<&str>)?;
+    let tracking_pause_30 = MenuItem::with_id(app, "tracking_pause_30", t("menu.pause_30"), true, None::<&str>)?;
+    let tracking_pause_60 = MenuItem::with_id(app, "tracking_pause_60", t("menu.pause_60"), true, None::<&str>)?;
+    let tracking_resume = MenuItem::with_id(app, "tracking_resume", t("menu.resume"), true, None::<&str>)?;
+    let tracking_menu = Submenu::with_items(
+        app,
+        t("menu.tracking"),
+        true,
+        &[
+            &tracking_pause_15,
+            &tracking_pause_30,
+            &tracking_pause_60,
+            &PredefinedMenuItem::separator(app)?,
+            &tracking_resume,
+        ],
+    )?;
+
+    
+    let edit_undo = PredefinedMenuItem::undo(app, Some(&t("menu.undo")))?;
+    let edit_redo = PredefinedMenuItem::redo(app, Some(&t("menu.redo")))?;
+    let edit_cut = PredefinedMenuItem::cut(app, Some(&t("menu.cut")))?;
+    let edit_copy = PredefinedMenuItem::copy(app, Some(&t("menu.copy")))?;
+    let edit_paste = PredefinedMenuItem::paste(app, Some(&t("menu.paste")))?;
+    let edit_select_all = PredefinedMenuItem::select_all(app, Some(&t("menu.select_all")))?;
     let edit_menu = Submenu::with_items(
         app,
-        "Edit",
+        t("menu.edit"),
         true,
         &[
             &edit_undo,
@@ -51,13 +70,15 @@ pub fn setup_app_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
     )?;
 
     
-    let help_item = MenuItem::with_id(app, "help", "Help", true, None::<&str>)?;
-    let help_menu = Submenu::with_items(app, "Help", true, &[&help_item])?;
+    let help_item = MenuItem::with_id(app, "help", t("menu.help"), true, None::<&str>)?;
+    let help_menu = Submenu::with_items(app, t("menu.help"), true, &[&help_item])?;
 
     
-    let app_menu = Menu::with_items(app, &[&about_menu, &file_menu, &edit_menu, &help_menu])?;
+    Menu::with_items(app, &[&about_menu, &file_menu, &edit_menu, &tracking_menu, &help_menu])
+}
 
-    app.set_menu(app_menu)?;
+pub fn setup_app_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    app.set_menu(build_app_menu(app)?)?;
 
     
     app.on_menu_event(|app, event| {
@@ -76,11 +97,21 @@ pub fn setup_app_menu(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>>
             "help" => {
                 push_log("info", "backend", "📖 Help: Window closes to tray. Use the menu bar or tray icon to reopen.".to_string());
             }
-            _ => {}
+            id => {
+                if let Some(action) = id.strip_prefix("tracking_") {
+                    crate::tracking::handle_tracking_menu_action(app, action);
+                }
+            }
         }
     });
 
     Ok(())
 }
 
+pub fn refresh_app_menu(app: &AppHandle) -> Result<(), String> {
+    let menu = build_app_menu(app).map_err(|e| format!("Failed to build app menu: {}", e))?;
+    app.set_menu(menu).map_err(|e| format!("Failed to set app menu: {}", e))?;
+    Ok(())
+}
+
 
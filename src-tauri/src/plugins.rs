@@ -0,0 +1,586 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::State;
+
+use crate::activity::local_date_range;
+use crate::auth::AuthState;
+use crate::breakdown::{fetch_summaries, BreakdownSource};
+use crate::config::ApiConfig;
+use crate::database::Database;
+use crate::projects::{access_token, resolve_base_url};
+use crate::push_log;
+use crate::setup::get_wakatime_config_path;
+
+const SILENT_AFTER_DAYS: i64 = 7;
+const CLI_VERSION_TIMEOUT_SECS: u64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginInstallation {
+    pub path: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EditorPluginStatus {
+    pub editor: String,
+    pub installations: Vec<PluginInstallation>,
+    pub last_heartbeat_at: Option<i64>,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CliInstallation {
+    pub path: String,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PluginReport {
+    pub editors: Vec<EditorPluginStatus>,
+    pub cli: Vec<CliInstallation>,
+    pub unrecognized_editors: Vec<String>,
+    // Local heartbeats only cover editors seen while this app was running, so a "silent"
+    // status from them is weaker evidence than one from the server.
+    pub activity_source: BreakdownSource,
+    pub generated_at: i64,
+}
+
+struct EditorDetector {
+    editor: &'static str,
+    aliases: &'static [&'static str],
+    find: fn(&Path) -> Vec<PluginInstallation>,
+}
+
+const DETECTORS: &[EditorDetector] = &[
+    EditorDetector {
+        editor: "VS Code",
+        aliases: &["vscode", "visualstudiocode", "code"],
+        find: find_vscode,
+    },
+    EditorDetector {
+        editor: "VSCodium",
+        aliases: &["vscodium", "codium"],
+        find: find_vscodium,
+    },
+    EditorDetector {
+        editor: "Cursor",
+        aliases: &["cursor"],
+        find: find_cursor,
+    },
+    EditorDetector {
+        editor: "JetBrains",
+        aliases: &[
+            "jetbrains", "intellij", "intellijidea", "pycharm", "webstorm", "phpstorm", "goland",
+            "clion", "rider", "rubymine", "datagrip", "rustrover", "androidstudio",
+        ],
+        find: find_jetbrains,
+    },
+    EditorDetector {
+        editor: "Vim",
+        aliases: &["vim", "gvim", "macvim"],
+        find: find_vim,
+    },
+    EditorDetector {
+        editor: "Neovim",
+        aliases: &["neovim", "nvim"],
+        find: find_neovim,
+    },
+    EditorDetector {
+        editor: "Sublime Text",
+        aliases: &["sublimetext", "sublime"],
+        find: find_sublime,
+    },
+    EditorDetector {
+        editor: "Zed",
+        aliases: &["zed"],
+        find: find_zed,
+    },
+];
+
+fn normalize_editor(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+fn get_home_dir() -> Result<PathBuf, String> {
+    let var = if cfg!(target_os = "windows") { "USERPROFILE" } else { "HOME" };
+    std::env::var(var)
+        .map(PathBuf::from)
+        .map_err(|_| format!("Failed to get {} directory", var))
+}
+
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var(var).ok().filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+fn subdirs(path: &Path) -> Vec<PathBuf> {
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|p| p.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn installation(path: PathBuf, version: Option<String>) -> PluginInstallation {
+    PluginInstallation {
+        path: path.to_string_lossy().to_string(),
+        version,
+    }
+}
+
+fn find_vscode_extensions(extension_dirs: &[PathBuf]) -> Vec<PluginInstallation> {
+    let prefix = "wakatime.vscode-wakatime-";
+    extension_dirs
+        .iter()
+        .flat_map(|dir| subdirs(dir))
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let version = name.strip_prefix(prefix)?.to_string();
+            Some(installation(path, Some(version)))
+        })
+        .collect()
+}
+
+fn find_vscode(home: &Path) -> Vec<PluginInstallation> {
+    find_vscode_extensions(&[
+        home.join(".vscode").join("extensions"),
+        home.join(".vscode-insiders").join("extensions"),
+        home.join(".vscode-server").join("extensions"),
+    ])
+}
+
+fn find_vscodium(home: &Path) -> Vec<PluginInstallation> {
+    find_vscode_extensions(&[home.join(".vscode-oss").join("extensions")])
+}
+
+fn find_cursor(home: &Path) -> Vec<PluginInstallation> {
+    find_vscode_extensions(&[home.join(".cursor").join("extensions")])
+}
+
+fn find_jetbrains(home: &Path) -> Vec<PluginInstallation> {
+    let mut product_dirs = Vec::new();
+    if cfg!(target_os = "windows") {
+        if let Some(appdata) = env_dir("APPDATA") {
+            product_dirs.extend(subdirs(&appdata.join("JetBrains")).into_iter().map(|p| p.join("plugins")));
+        }
+    } else if cfg!(target_os = "macos") {
+        let base = home.join("Library").join("Application Support").join("JetBrains");
+        product_dirs.extend(subdirs(&base).into_iter().map(|p| p.join("plugins")));
+    } else {
+        product_dirs.extend(subdirs(&home.join(".local").join("share").join("JetBrains")));
+    }
+
+    product_dirs
+        .into_iter()
+        .map(|dir| dir.join("WakaTime"))
+        .filter(|path| path.is_dir())
+        .map(|path| installation(path, None))
+        .collect()
+}
+
+fn find_vim_plugin(roots: &[PathBuf]) -> Vec<PluginInstallation> {
+    let mut candidates = Vec::new();
+    for root in roots {
+        for manager in ["bundle", "plugged", "lazy"] {
+            candidates.push(root.join(manager).join("vim-wakatime"));
+        }
+        for pack in subdirs(&root.join("pack")) {
+            candidates.push(pack.join("start").join("vim-wakatime"));
+            candidates.push(pack.join("opt").join("vim-wakatime"));
+        }
+    }
+
+    candidates
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .map(|path| installation(path, None))
+        .collect()
+}
+
+fn find_vim(home: &Path) -> Vec<PluginInstallation> {
+    find_vim_plugin(&[home.join(".vim"), home.join("vimfiles")])
+}
+
+fn find_neovim(home: &Path) -> Vec<PluginInstallation> {
+    let mut roots = vec![
+        home.join(".config").join("nvim"),
+        home.join(".local").join("share").join("nvim"),
+        home.join(".local").join("share").join("nvim").join("site"),
+    ];
+    if let Some(local_appdata) = env_dir("LOCALAPPDATA") {
+        roots.push(local_appdata.join("nvim"));
+        roots.push(local_appdata.join("nvim-data"));
+        roots.push(local_appdata.join("nvim-data").join("site"));
+    }
+    find_vim_plugin(&roots)
+}
+
+fn find_sublime(home: &Path) -> Vec<PluginInstallation> {
+    let mut data_dirs = Vec::new();
+    if cfg!(target_os = "windows") {
+        if let Some(appdata) = env_dir("APPDATA") {
+            data_dirs.push(appdata.join("Sublime Text"));
+            data_dirs.push(appdata.join("Sublime Text 3"));
+        }
+    } else if cfg!(target_os = "macos") {
+        let base = home.join("Library").join("Application Support");
+        data_dirs.push(base.join("Sublime Text"));
+        data_dirs.push(base.join("Sublime Text 3"));
+    } else {
+        data_dirs.push(home.join(".config").join("sublime-text"));
+        data_dirs.push(home.join(".config").join("sublime-text-3"));
+    }
+
+    data_dirs
+        .into_iter()
+        .flat_map(|dir| {
+            [
+                dir.join("Packages").join("WakaTime"),
+                dir.join("Installed Packages").join("WakaTime.sublime-package"),
+            ]
+        })
+        .filter(|path| path.exists())
+        .map(|path| installation(path, None))
+        .collect()
+}
+
+fn find_zed(home: &Path) -> Vec<PluginInstallation> {
+    let extensions_dir = if cfg!(target_os = "windows") {
+        env_dir("LOCALAPPDATA").map(|dir| dir.join("Zed").join("extensions"))
+    } else if cfg!(target_os = "macos") {
+        Some(home.join("Library").join("Application Support").join("Zed").join("extensions"))
+    } else {
+        Some(home.join(".local").join("share").join("zed").join("extensions"))
+    };
+
+    extensions_dir
+        .map(|dir| dir.join("installed").join("wakatime"))
+        .filter(|path| path.is_dir())
+        .map(|path| {
+            let version = read_toml_version(&path.join("extension.toml"));
+            vec![installation(path, version)]
+        })
+        .unwrap_or_default()
+}
+
+fn read_toml_version(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        if key.trim() == "version" {
+            Some(value.trim().trim_matches('"').to_string())
+        } else {
+            None
+        }
+    })
+}
+
+async fn find_cli_installations() -> Vec<CliInstallation> {
+    let Some(wakatime_dir) = get_wakatime_config_path()
+        .ok()
+        .and_then(|path| Path::new(&path).parent().map(|p| p.join(".wakatime")))
+    else {
+        return Vec::new();
+    };
+
+    let Ok(entries) = fs::read_dir(&wakatime_dir) else {
+        return Vec::new();
+    };
+
+    let mut binaries: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && path
+                    .file_name()
+                    .map(|name| {
+                        let name = name.to_string_lossy();
+                        name.starts_with("wakatime-cli") && !name.ends_with(".zip") && !name.ends_with(".old")
+                    })
+                    .unwrap_or(false)
+        })
+        .collect();
+    binaries.sort();
+
+    let mut installations = Vec::new();
+    for path in binaries {
+        let version = read_cli_version(&path).await;
+        installations.push(CliInstallation {
+            path: path.to_string_lossy().to_string(),
+            version,
+        });
+    }
+    installations
+}
+
+async fn read_cli_version(path: &Path) -> Option<String> {
+    let output = tokio::time::timeout(
+        std::time::Duration::from_secs(CLI_VERSION_TIMEOUT_SECS),
+        tokio::process::Command::new(path).arg("--version").output(),
+    )
+    .await;
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => {
+            let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if version.is_empty() {
+                None
+            } else {
+                Some(version)
+            }
+        }
+        Ok(Ok(output)) => {
+            push_log("warn", "backend", format!("{} --version exited with {}", path.display(), output.status));
+            None
+        }
+        Ok(Err(e)) => {
+            push_log("warn", "backend", format!("Failed to run {} --version: {}", path.display(), e));
+            None
+        }
+        Err(_) => {
+            push_log("warn", "backend", format!("{} --version timed out", path.display()));
+            None
+        }
+    }
+}
+
+// Summaries are per day, so an editor's last activity is the local start of the most
+// recent day it has time on.
+async fn server_recent_editors(
+    api_config: &ApiConfig,
+    state: &Arc<tauri::async_runtime::Mutex<AuthState>>,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<(String, i64)>, String> {
+    let token = access_token(state).await?;
+    let summaries = fetch_summaries(&resolve_base_url(api_config), &token, None, start_date, end_date).await?;
+
+    let mut last_seen: HashMap<String, i64> = HashMap::new();
+    for day in summaries["data"].as_array().into_iter().flatten() {
+        let Some(date) = day["range"]["date"]
+            .as_str()
+            .or_else(|| day["range"]["start"].as_str().and_then(|start| start.get(..10)))
+        else {
+            continue;
+        };
+        let Ok((day_start, _)) = local_date_range(date, date) else {
+            continue;
+        };
+        for editor in day["editors"].as_array().into_iter().flatten() {
+            let (Some(name), Some(seconds)) = (editor["name"].as_str(), editor["total_seconds"].as_f64()) else {
+                continue;
+            };
+            if seconds > 0.0 {
+                let seen = last_seen.entry(name.to_string()).or_insert(day_start);
+                *seen = (*seen).max(day_start);
+            }
+        }
+    }
+    Ok(last_seen.into_iter().collect())
+}
+
+async fn local_recent_editors(since: i64) -> Vec<(String, i64)> {
+    match Database::new().await {
+        Ok(db) => db.get_recent_editors(since).await.unwrap_or_else(|e| {
+            push_log("warn", "backend", format!("Failed to load recent editors: {}", e));
+            Vec::new()
+        }),
+        Err(e) => {
+            push_log("warn", "backend", format!("Failed to open database for plugin report: {}", e));
+            Vec::new()
+        }
+    }
+}
+
+// An installed plugin with no recent activity from its editor is reported as silent.
+fn editor_statuses(home: &Path, recent_editors: &[(String, i64)]) -> (Vec<EditorPluginStatus>, Vec<String>) {
+    let mut matched_editors = vec![false; recent_editors.len()];
+    let mut editors = Vec::new();
+
+    for detector in DETECTORS {
+        let installations = (detector.find)(home);
+
+        let mut last_heartbeat_at = None;
+        for (index, (editor, last_seen)) in recent_editors.iter().enumerate() {
+            if detector.aliases.contains(&normalize_editor(editor).as_str()) {
+                matched_editors[index] = true;
+                last_heartbeat_at = last_heartbeat_at.max(Some(*last_seen));
+            }
+        }
+
+        let status = match (installations.is_empty(), last_heartbeat_at.is_some()) {
+            (false, true) => "active",
+            (false, false) => "silent",
+            (true, true) => "active_without_detected_plugin",
+            (true, false) => "not_installed",
+        };
+
+        editors.push(EditorPluginStatus {
+            editor: detector.editor.to_string(),
+            installations,
+            last_heartbeat_at,
+            status: status.to_string(),
+        });
+    }
+
+    let unrecognized_editors = recent_editors
+        .iter()
+        .zip(matched_editors)
+        .filter(|(_, matched)| !matched)
+        .map(|((editor, _), _)| editor.clone())
+        .collect();
+
+    (editors, unrecognized_editors)
+}
+
+#[tauri::command]
+pub async fn get_editor_plugin_report(
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<PluginReport, String> {
+    let home = get_home_dir()?;
+    let now = chrono::Utc::now().timestamp();
+    let today = chrono::Local::now().date_naive();
+    let start_date = (today - chrono::Duration::days(SILENT_AFTER_DAYS)).format("%Y-%m-%d").to_string();
+    let end_date = today.format("%Y-%m-%d").to_string();
+
+    let (recent_editors, activity_source) =
+        match server_recent_editors(&api_config, state.inner(), &start_date, &end_date).await {
+            Ok(editors) => (editors, BreakdownSource::Server),
+            Err(e) => {
+                push_log("warn", "backend", format!("Plugin report using local heartbeats: {}", e));
+                (local_recent_editors(now - SILENT_AFTER_DAYS * 24 * 3600).await, BreakdownSource::Local)
+            }
+        };
+
+    let (editors, unrecognized_editors) = editor_statuses(&home, &recent_editors);
+    let cli = find_cli_installations().await;
+
+    push_log("info", "backend", format!(
+        "Plugin report: {} editors with plugins, {} wakatime-cli binaries",
+        editors.iter().filter(|e| !e.installations.is_empty()).count(),
+        cli.len()
+    ));
+
+    Ok(PluginReport {
+        editors,
+        cli,
+        unrecognized_editors,
+        activity_source,
+        generated_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TempHome(PathBuf);
+
+    impl TempHome {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("hackatime-plugins-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            TempHome(path)
+        }
+
+        fn dir(&self, relative: &str) -> PathBuf {
+            let path = self.0.join(relative);
+            fs::create_dir_all(&path).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempHome {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn versions(installations: &[PluginInstallation]) -> Vec<Option<String>> {
+        installations.iter().map(|i| i.version.clone()).collect()
+    }
+
+    #[test]
+    fn vscode_family_extensions_report_their_version() {
+        let home = TempHome::new("vscode");
+        home.dir(".vscode/extensions/wakatime.vscode-wakatime-24.6.0");
+        home.dir(".vscode-server/extensions/wakatime.vscode-wakatime-24.5.1");
+        home.dir(".vscode/extensions/esbenp.prettier-vscode-10.1.0");
+        home.dir(".vscode-oss/extensions/wakatime.vscode-wakatime-24.0.0");
+        home.dir(".cursor/extensions/wakatime.vscode-wakatime-23.9.9");
+
+        let mut vscode = versions(&find_vscode(&home.0));
+        vscode.sort();
+        assert_eq!(vscode, vec![Some("24.5.1".to_string()), Some("24.6.0".to_string())]);
+        assert_eq!(versions(&find_vscodium(&home.0)), vec![Some("24.0.0".to_string())]);
+        assert_eq!(versions(&find_cursor(&home.0)), vec![Some("23.9.9".to_string())]);
+    }
+
+    #[test]
+    fn vim_and_neovim_plugins_are_found_in_common_plugin_managers() {
+        let home = TempHome::new("vim");
+        assert!(find_vim(&home.0).is_empty());
+        assert!(find_neovim(&home.0).is_empty());
+
+        home.dir(".vim/pack/plugins/start/vim-wakatime");
+        home.dir(".local/share/nvim/lazy/vim-wakatime");
+
+        assert_eq!(find_vim(&home.0).len(), 1);
+        assert_eq!(find_neovim(&home.0).len(), 1);
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn linux_jetbrains_sublime_and_zed_plugins_are_found() {
+        let home = TempHome::new("linux");
+        assert!(find_jetbrains(&home.0).is_empty());
+        assert!(find_sublime(&home.0).is_empty());
+        assert!(find_zed(&home.0).is_empty());
+
+        home.dir(".local/share/JetBrains/PyCharm2024.1/WakaTime");
+        home.dir(".local/share/JetBrains/GoLand2024.1");
+        home.dir(".config/sublime-text/Packages/WakaTime");
+        let zed = home.dir(".local/share/zed/extensions/installed/wakatime");
+        fs::write(zed.join("extension.toml"), "id = \"wakatime\"\nversion = \"0.1.4\"\n").unwrap();
+
+        assert_eq!(find_jetbrains(&home.0).len(), 1);
+        assert_eq!(find_sublime(&home.0).len(), 1);
+        assert_eq!(versions(&find_zed(&home.0)), vec![Some("0.1.4".to_string())]);
+    }
+
+    fn status_of<'a>(editors: &'a [EditorPluginStatus], editor: &str) -> &'a str {
+        &editors.iter().find(|e| e.editor == editor).unwrap().status
+    }
+
+    #[test]
+    fn installed_plugins_without_recent_activity_are_silent() {
+        let home = TempHome::new("silent");
+        home.dir(".vscode/extensions/wakatime.vscode-wakatime-24.6.0");
+        home.dir(".vim/bundle/vim-wakatime");
+
+        let recent = vec![
+            ("VS Code".to_string(), 1_700_000_000),
+            ("IntelliJ IDEA".to_string(), 1_700_000_100),
+            ("Emacs".to_string(), 1_700_000_200),
+        ];
+        let (editors, unrecognized) = editor_statuses(&home.0, &recent);
+
+        assert_eq!(status_of(&editors, "VS Code"), "active");
+        assert_eq!(status_of(&editors, "Vim"), "silent");
+        assert_eq!(status_of(&editors, "JetBrains"), "active_without_detected_plugin");
+        assert_eq!(status_of(&editors, "Zed"), "not_installed");
+        assert_eq!(unrecognized, vec!["Emacs".to_string()]);
+    }
+}
@@ -4,13 +4,20 @@ use std::path::PathBuf;
 use tauri::AppHandle;
 use tauri_plugin_autostart::ManagerExt;
 use crate::database::get_hackatime_config_dir;
+use crate::format::DurationStyle;
 use crate::push_log;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Preferences {
     pub autostart_enabled: bool,
     pub notifications_enabled: bool,
     pub discord_rpc_enabled: bool,
+    pub tray_time_display_enabled: bool,
+    pub daily_goal_hours: f64,
+    pub log_level: String,
+    pub locale: String,
+    pub duration_style: DurationStyle,
 }
 
 impl Default for Preferences {
@@ -19,6 +26,11 @@ impl Default for Preferences {
             autostart_enabled: true,
             notifications_enabled: true,
             discord_rpc_enabled: true,
+            tray_time_display_enabled: false,
+            daily_goal_hours: 2.0,
+            log_level: "info".to_string(),
+            locale: crate::i18n::AUTO_LOCALE.to_string(),
+            duration_style: DurationStyle::default(),
         }
     }
 }
@@ -132,3 +144,104 @@ pub fn get_discord_rpc_enabled() -> Result<bool, String> {
     Ok(preferences.discord_rpc_enabled)
 }
 
+#[tauri::command]
+pub async fn set_tray_time_display_enabled(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let mut preferences = load_preferences().unwrap_or_default();
+    preferences.tray_time_display_enabled = enabled;
+    save_preferences(&preferences)?;
+    
+    if enabled {
+        push_log("info", "backend", "Tray time display enabled".to_string());
+    } else {
+        push_log("info", "backend", "Tray time display disabled".to_string());
+    }
+    
+    crate::tray::refresh_tray_status(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_tray_time_display_enabled() -> Result<bool, String> {
+    let preferences = load_preferences().unwrap_or_default();
+    Ok(preferences.tray_time_display_enabled)
+}
+
+#[tauri::command]
+pub async fn set_daily_goal_hours(app: AppHandle, hours: f64) -> Result<(), String> {
+    if !hours.is_finite() || hours <= 0.0 || hours > 24.0 {
+        return Err("Daily goal must be between 0 and 24 hours".to_string());
+    }
+    
+    let mut preferences = load_preferences().unwrap_or_default();
+    preferences.daily_goal_hours = hours;
+    save_preferences(&preferences)?;
+    
+    push_log("info", "backend", format!("Daily goal set to {:.1}h", hours));
+    
+    crate::tray::refresh_tray_status(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_daily_goal_hours() -> Result<f64, String> {
+    let preferences = load_preferences().unwrap_or_default();
+    Ok(preferences.daily_goal_hours)
+}
+
+#[tauri::command]
+pub fn set_log_level(level: String) -> Result<(), String> {
+    let filter = crate::logging::parse_level(&level)
+        .ok_or_else(|| format!("Unknown log level: {}", level))?;
+    
+    let mut preferences = load_preferences().unwrap_or_default();
+    preferences.log_level = level.to_lowercase();
+    save_preferences(&preferences)?;
+    
+    crate::logging::set_level(filter);
+    push_log("info", "backend", format!("Log level set to {}", preferences.log_level));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_log_level() -> Result<String, String> {
+    let preferences = load_preferences().unwrap_or_default();
+    Ok(preferences.log_level)
+}
+
+#[tauri::command]
+pub async fn set_locale(app: AppHandle, locale: String) -> Result<(), String> {
+    if !crate::i18n::is_valid_preference(&locale) {
+        return Err(format!("Unsupported locale: {}", locale));
+    }
+
+    let mut preferences = load_preferences().unwrap_or_default();
+    preferences.locale = locale.to_lowercase();
+    save_preferences(&preferences)?;
+
+    crate::i18n::init(&preferences.locale);
+    push_log("info", "backend", format!("Locale set to {}", preferences.locale));
+
+    crate::menu::refresh_app_menu(&app)?;
+    crate::tray::refresh_tray_menu(&app).await?;
+    crate::tray::refresh_tray_status(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn set_duration_style(app: AppHandle, style: DurationStyle) -> Result<(), String> {
+    let mut preferences = load_preferences().unwrap_or_default();
+    preferences.duration_style = style;
+    save_preferences(&preferences)?;
+
+    crate::format::init(style);
+    push_log("info", "backend", format!("Duration style set to {:?}", style));
+
+    crate::tray::refresh_tray_status(&app).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_duration_style() -> Result<DurationStyle, String> {
+    let preferences = load_preferences().unwrap_or_default();
+    Ok(preferences.duration_style)
+}
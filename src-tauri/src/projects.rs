@@ -1,117 +1,600 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, State};
 
 use crate::auth::AuthState;
+use crate::breakdown::{fetch_summary_totals, BreakdownSource};
 use crate::config::ApiConfig;
+use crate::database::{Database, ProjectMetadata};
 use crate::push_log;
+use crate::repositories::RepositoryInfo;
 
-#[tauri::command]
-pub async fn get_projects(
-    api_config: ApiConfig,
-    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
-) -> Result<serde_json::Value, String> {
+const PROJECTS_CACHE_KEY: &str = "projects:list";
+const PROJECTS_FRESH_SECS: i64 = 5 * 60;
+const PROJECTS_CACHE_TTL_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub name: String,
+    pub total_seconds: f64,
+    pub languages: Vec<String>,
+    pub most_recent_heartbeat: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<ProjectMetadata>,
+    #[serde(default)]
+    pub repositories: Vec<RepositoryInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectDetails {
+    #[serde(flatten)]
+    pub project: Project,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectsResponse {
+    pub projects: Vec<Project>,
+    pub fetched_at: i64,
+    pub is_stale: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEntry<T> {
+    fetched_at: i64,
+    data: T,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSort {
+    #[default]
+    TotalTime,
+    LastActive,
+    Name,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct ProjectFilter {
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub active_since: Option<String>,
+    #[serde(default)]
+    pub active_until: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagProjectStat {
+    pub name: String,
+    pub total_seconds: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagStat {
+    pub tag: String,
+    pub total_seconds: f64,
+    pub projects: Vec<TagProjectStat>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagStatsResponse {
+    pub start_date: String,
+    pub end_date: String,
+    pub source: BreakdownSource,
+    pub tags: Vec<TagStat>,
+    pub untagged_seconds: f64,
+}
+
+impl Project {
+    fn from_value(value: &serde_json::Value) -> Option<Self> {
+        let name = value["name"].as_str().map(str::trim).filter(|n| !n.is_empty())?;
+
+        let total_seconds = match &value["total_seconds"] {
+            serde_json::Value::Number(n) => n.as_f64().unwrap_or(0.0),
+            serde_json::Value::String(s) => s.parse().unwrap_or(0.0),
+            _ => 0.0,
+        };
+
+        let languages = value["languages"]
+            .as_array()
+            .map(|languages| {
+                languages
+                    .iter()
+                    .filter_map(|language| match language {
+                        serde_json::Value::String(s) => Some(s.clone()),
+                        other => other["name"].as_str().map(str::to_string),
+                    })
+                    .filter(|language| !language.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let most_recent_heartbeat = match &value["most_recent_heartbeat"] {
+            serde_json::Value::String(s) if !s.is_empty() => Some(s.clone()),
+            serde_json::Value::Number(n) => n
+                .as_f64()
+                .and_then(|secs| chrono::DateTime::from_timestamp(secs as i64, 0))
+                .map(|dt| dt.to_rfc3339()),
+            _ => None,
+        };
+
+        Some(Self {
+            name: name.to_string(),
+            total_seconds,
+            languages,
+            most_recent_heartbeat,
+            metadata: None,
+            repositories: Vec::new(),
+        })
+    }
+
+    fn display_name(&self) -> &str {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.alias.as_deref())
+            .unwrap_or(&self.name)
+    }
+
+    fn is_archived(&self) -> bool {
+        self.metadata.as_ref().is_some_and(|metadata| metadata.archived)
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        self.metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    fn last_active(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.most_recent_heartbeat
+            .as_deref()
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+    }
+}
+
+async fn attach_metadata(projects: &mut [Project]) {
+    let db = match Database::new().await {
+        Ok(db) => db,
+        Err(e) => {
+            push_log("warn", "backend", format!("Failed to load project metadata: {}", e));
+            return;
+        }
+    };
+
+    match db.get_all_project_metadata().await {
+        Ok(mut metadata) => {
+            for project in projects.iter_mut() {
+                project.metadata = metadata.remove(&project.name);
+            }
+        }
+        Err(e) => push_log("warn", "backend", format!("Failed to load project metadata: {}", e)),
+    }
+
+    match db.list_repositories().await {
+        Ok(repositories) => {
+            let mut by_project: HashMap<String, Vec<RepositoryInfo>> = HashMap::new();
+            for indexed in repositories {
+                if let Some(project) = indexed.project {
+                    by_project.entry(project).or_default().push(indexed.repository);
+                }
+            }
+            for project in projects.iter_mut() {
+                project.repositories = by_project.remove(&project.name).unwrap_or_default();
+            }
+        }
+        Err(e) => push_log("warn", "backend", format!("Failed to load repositories: {}", e)),
+    }
+}
+
+fn normalize_metadata(mut metadata: ProjectMetadata) -> Result<ProjectMetadata, String> {
+    let clean = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+    metadata.project_name = metadata.project_name.trim().to_string();
+    if metadata.project_name.is_empty() {
+        return Err("Project name must not be empty".to_string());
+    }
+
+    metadata.alias = clean(metadata.alias);
+    metadata.notes = clean(metadata.notes);
+    metadata.color = clean(metadata.color);
+    if let Some(color) = &metadata.color {
+        let hex = color.strip_prefix('#').unwrap_or_default();
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Color '{}' must be a hex value like #FB4B20", color));
+        }
+    }
+
+    let mut tags: Vec<String> = metadata
+        .tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    metadata.tags = tags;
+
+    Ok(metadata)
+}
+
+pub(crate) fn resolve_base_url(api_config: &ApiConfig) -> String {
+    if api_config.base_url.is_empty() {
+        "https://hackatime.hackclub.com".to_string()
+    } else {
+        api_config.base_url.clone()
+    }
+}
+
+pub(crate) async fn access_token(state: &Arc<tauri::async_runtime::Mutex<AuthState>>) -> Result<String, String> {
     let auth_state = state.lock().await;
 
     if !auth_state.is_authenticated {
         return Err("Not authenticated".to_string());
     }
 
-    let base_url = if api_config.base_url.is_empty() {
-        "https://hackatime.hackclub.com"
-    } else {
-        &api_config.base_url
-    };
-
-    let access_token = auth_state
+    auth_state
         .access_token
-        .as_ref()
-        .ok_or("No access token available")?;
+        .clone()
+        .ok_or_else(|| "No access token available".to_string())
+}
 
+async fn read_cache<T: serde::de::DeserializeOwned>(cache_key: &str) -> Option<CachedEntry<T>> {
+    let db = Database::new().await.ok()?;
+    let cached = db.get_cached_data(cache_key).await.ok()??;
+    serde_json::from_str(&cached).ok()
+}
+
+async fn write_cache<T: Serialize>(cache_key: &str, data: &T) -> Result<i64, String> {
+    let fetched_at = chrono::Utc::now().timestamp();
+    let entry = CachedEntry { fetched_at, data };
+    let json = serde_json::to_string(&entry).map_err(|e| format!("Failed to serialize cache entry: {}", e))?;
+
+    let db = Database::new().await?;
+    db.set_cached_data(cache_key, &json, PROJECTS_CACHE_TTL_DAYS).await?;
+    Ok(fetched_at)
+}
+
+fn is_fresh(fetched_at: i64) -> bool {
+    chrono::Utc::now().timestamp() - fetched_at < PROJECTS_FRESH_SECS
+}
+
+pub(crate) async fn fetch_json(url: &str, access_token: &str, what: &str) -> Result<serde_json::Value, String> {
     let client = reqwest::Client::new();
     let response = client
-        .get(&format!(
-            "{}/api/v1/authenticated/projects",
-            base_url
-        ))
+        .get(url)
         .bearer_auth(access_token)
         .send()
         .await
-        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+        .map_err(|e| format!("Failed to fetch {}: {}", what, e))?;
 
     if !response.status().is_success() {
         let error_text = response
             .text()
             .await
             .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("Projects request failed: {}", error_text));
+        return Err(format!("{} request failed: {}", what, error_text));
     }
 
-    let projects_response: serde_json::Value = response
+    response
         .json()
         .await
-        .map_err(|e| format!("Failed to parse projects response: {}", e))?;
-
-    push_log(
-        "info",
-        "backend",
-        format!(
-            "RAW PROJECTS API RESPONSE: {}",
-            serde_json::to_string_pretty(&projects_response)
-                .unwrap_or_else(|_| "Failed to serialize".to_string())
+        .map_err(|e| format!("Failed to parse {} response: {}", what, e))
+}
+
+async fn fetch_projects(base_url: &str, access_token: &str) -> Result<Vec<Project>, String> {
+    let response = fetch_json(
+        &format!("{}/api/v1/authenticated/projects", base_url),
+        access_token,
+        "projects",
+    )
+    .await?;
+
+    let projects: Vec<Project> = response["projects"]
+        .as_array()
+        .map(|projects| projects.iter().filter_map(Project::from_value).collect())
+        .unwrap_or_default();
+
+    push_log("info", "backend", format!("Fetched {} projects", projects.len()));
+    write_cache(PROJECTS_CACHE_KEY, &projects).await?;
+    Ok(projects)
+}
+
+async fn fetch_project_details(base_url: &str, access_token: &str, project_name: &str) -> Result<ProjectDetails, String> {
+    let response = fetch_json(
+        &format!(
+            "{}/api/v1/authenticated/projects/{}",
+            base_url,
+            urlencoding::encode(project_name)
         ),
-    );
+        access_token,
+        "project details",
+    )
+    .await?;
+
+    let body = response.get("project").unwrap_or(&response);
+    let project = Project::from_value(body).unwrap_or(Project {
+        name: project_name.to_string(),
+        total_seconds: 0.0,
+        languages: Vec::new(),
+        most_recent_heartbeat: None,
+        metadata: None,
+        repositories: Vec::new(),
+    });
+
+    let mut extra = body.as_object().cloned().unwrap_or_default();
+    for key in ["name", "total_seconds", "languages", "most_recent_heartbeat", "metadata", "repositories"] {
+        extra.remove(key);
+    }
 
-    Ok(projects_response)
+    let details = ProjectDetails { project, extra };
+    write_cache(&format!("projects:details:{}", project_name), &details).await?;
+    Ok(details)
+}
+
+// Serve whatever is cached straight away and refresh in the background once it is older
+// than PROJECTS_FRESH_SECS, so the list renders instantly and catches up a moment later.
+pub async fn load_projects(app: &tauri::AppHandle, base_url: String, access_token: String) -> Result<ProjectsResponse, String> {
+    if let Some(cached) = read_cache::<Vec<Project>>(PROJECTS_CACHE_KEY).await {
+        let is_stale = !is_fresh(cached.fetched_at);
+        if is_stale {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                match fetch_projects(&base_url, &access_token).await {
+                    Ok(mut projects) => {
+                        attach_metadata(&mut projects).await;
+                        let _ = app.emit("projects-updated", ProjectsResponse {
+                            projects,
+                            fetched_at: chrono::Utc::now().timestamp(),
+                            is_stale: false,
+                        });
+                    }
+                    Err(e) => push_log("warn", "backend", format!("Background project refresh failed: {}", e)),
+                }
+            });
+        }
+
+        let mut projects = cached.data;
+        attach_metadata(&mut projects).await;
+        return Ok(ProjectsResponse {
+            projects,
+            fetched_at: cached.fetched_at,
+            is_stale,
+        });
+    }
+
+    let mut projects = fetch_projects(&base_url, &access_token).await?;
+    attach_metadata(&mut projects).await;
+    Ok(ProjectsResponse {
+        projects,
+        fetched_at: chrono::Utc::now().timestamp(),
+        is_stale: false,
+    })
+}
+
+#[tauri::command]
+pub async fn get_projects(
+    api_config: ApiConfig,
+    app_handle: tauri::AppHandle,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<ProjectsResponse, String> {
+    let access_token = access_token(state.inner()).await?;
+    load_projects(&app_handle, resolve_base_url(&api_config), access_token).await
 }
 
 #[tauri::command]
 pub async fn get_project_details(
     project_name: String,
     api_config: ApiConfig,
+    app_handle: tauri::AppHandle,
     state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
-) -> Result<serde_json::Value, String> {
-    let auth_state = state.lock().await;
+) -> Result<ProjectDetails, String> {
+    let access_token = access_token(state.inner()).await?;
+    let base_url = resolve_base_url(&api_config);
+    let cache_key = format!("projects:details:{}", project_name);
 
-    if !auth_state.is_authenticated {
-        return Err("Not authenticated".to_string());
+    if let Some(cached) = read_cache::<ProjectDetails>(&cache_key).await {
+        if !is_fresh(cached.fetched_at) {
+            tauri::async_runtime::spawn(async move {
+                match fetch_project_details(&base_url, &access_token, &project_name).await {
+                    Ok(mut details) => {
+                        attach_metadata(std::slice::from_mut(&mut details.project)).await;
+                        let _ = app_handle.emit("project-details-updated", details);
+                    }
+                    Err(e) => push_log("warn", "backend", format!("Background project details refresh failed: {}", e)),
+                }
+            });
+        }
+        let mut details = cached.data;
+        attach_metadata(std::slice::from_mut(&mut details.project)).await;
+        return Ok(details);
     }
 
-    let base_url = if api_config.base_url.is_empty() {
-        "https://hackatime.hackclub.com"
-    } else {
-        &api_config.base_url
+    let mut details = fetch_project_details(&base_url, &access_token, &project_name).await?;
+    attach_metadata(std::slice::from_mut(&mut details.project)).await;
+    Ok(details)
+}
+
+#[tauri::command]
+pub async fn search_projects(
+    query: Option<String>,
+    sort: Option<ProjectSort>,
+    filter: Option<ProjectFilter>,
+    api_config: ApiConfig,
+    app_handle: tauri::AppHandle,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<Vec<Project>, String> {
+    let projects = match read_cache::<Vec<Project>>(PROJECTS_CACHE_KEY).await {
+        Some(cached) => {
+            let mut projects = cached.data;
+            attach_metadata(&mut projects).await;
+            projects
+        }
+        None => {
+            let access_token = access_token(state.inner()).await?;
+            load_projects(&app_handle, resolve_base_url(&api_config), access_token)
+                .await?
+                .projects
+        }
     };
 
-    let access_token = auth_state
-        .access_token
-        .as_ref()
-        .ok_or("No access token available")?;
+    let query = query.unwrap_or_default().trim().to_lowercase();
+    let filter = filter.unwrap_or_default();
+    let parse_date = |value: &Option<String>| {
+        value
+            .as_deref()
+            .and_then(|d| chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+    };
+    let since = parse_date(&filter.active_since);
+    let until = parse_date(&filter.active_until);
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&format!(
-            "{}/api/v1/authenticated/projects/{}",
-            base_url,
-            urlencoding::encode(&project_name)
-        ))
-        .bearer_auth(access_token)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch project details: {}", e))?;
+    let mut results: Vec<Project> = projects
+        .into_iter()
+        .filter(|project| filter.include_archived || !project.is_archived())
+        .filter(|project| filter.tag.as_deref().is_none_or(|tag| project.has_tag(tag)))
+        .filter(|project| {
+            query.is_empty()
+                || project.name.to_lowercase().contains(&query)
+                || project.display_name().to_lowercase().contains(&query)
+        })
+        .filter(|project| {
+            filter.language.as_deref().is_none_or(|language| {
+                project.languages.iter().any(|l| l.eq_ignore_ascii_case(language))
+            })
+        })
+        .filter(|project| {
+            if since.is_none() && until.is_none() {
+                return true;
+            }
+            let Some(last_active) = project.last_active().map(|dt| dt.date_naive()) else {
+                return false;
+            };
+            since.is_none_or(|since| last_active >= since) && until.is_none_or(|until| last_active <= until)
+        })
+        .collect();
 
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!("Project details request failed: {}", error_text));
+    match sort.unwrap_or_default() {
+        ProjectSort::TotalTime => results.sort_by(|a, b| b.total_seconds.total_cmp(&a.total_seconds)),
+        ProjectSort::LastActive => results.sort_by_key(|project| std::cmp::Reverse(project.last_active())),
+        ProjectSort::Name => results.sort_by_key(|project| project.display_name().to_lowercase()),
     }
 
-    let project_response: serde_json::Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse project response: {}", e))?;
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn get_project_metadata(project_name: String) -> Result<ProjectMetadata, String> {
+    let db = Database::new().await?;
+    Ok(db
+        .get_all_project_metadata()
+        .await?
+        .remove(&project_name)
+        .unwrap_or(ProjectMetadata {
+            project_name,
+            ..Default::default()
+        }))
+}
+
+#[tauri::command]
+pub async fn set_project_metadata(metadata: ProjectMetadata) -> Result<ProjectMetadata, String> {
+    let metadata = normalize_metadata(metadata)?;
+    let db = Database::new().await?;
+    db.save_project_metadata(&metadata).await?;
+    push_log("info", "backend", format!("Updated metadata for project {}", metadata.project_name));
+    Ok(metadata)
+}
 
-    Ok(project_response)
+#[tauri::command]
+pub async fn list_project_tags() -> Result<Vec<String>, String> {
+    let db = Database::new().await?;
+    let mut tags: Vec<String> = db
+        .get_all_project_metadata()
+        .await?
+        .into_values()
+        .flat_map(|metadata| metadata.tags)
+        .collect();
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
 }
 
+async fn load_project_seconds(
+    api_config: &ApiConfig,
+    state: &Arc<tauri::async_runtime::Mutex<AuthState>>,
+    start_date: &str,
+    end_date: &str,
+) -> Result<(BreakdownSource, HashMap<String, f64>), String> {
+    let server = match access_token(state).await {
+        Ok(token) => fetch_summary_totals(&resolve_base_url(api_config), &token, None, start_date, end_date, "projects").await,
+        Err(e) => Err(e),
+    };
+
+    match server {
+        Ok(totals) if !totals.is_empty() => return Ok((BreakdownSource::Server, totals)),
+        Ok(_) => push_log("debug", "backend", "No server project totals, using local heartbeats".to_string()),
+        Err(e) => push_log("warn", "backend", format!("Falling back to local project totals: {}", e)),
+    }
+
+    let (start, end) = crate::activity::local_date_range(start_date, end_date)?;
+    let totals = crate::activity::local_project_seconds(start, end).await?;
+    Ok((BreakdownSource::Local, totals))
+}
+
+// A project with several tags counts toward each of them, so the tag totals can add up to
+// more than the time actually coded.
+#[tauri::command]
+pub async fn get_tag_stats(
+    start_date: Option<String>,
+    end_date: Option<String>,
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<TagStatsResponse, String> {
+    let (start_date, end_date) = crate::activity::DateRange { start_date, end_date }.resolve();
+
+    let (source, project_seconds) = load_project_seconds(&api_config, state.inner(), &start_date, &end_date).await?;
+    let metadata = Database::new().await?.get_all_project_metadata().await?;
+
+    let mut by_tag: BTreeMap<String, HashMap<String, f64>> = BTreeMap::new();
+    let mut untagged_seconds = 0.0;
+    for (project, seconds) in &project_seconds {
+        let tags = metadata.get(project).map(|m| m.tags.as_slice()).unwrap_or_default();
+        if tags.is_empty() {
+            untagged_seconds += seconds;
+        }
+        for tag in tags {
+            *by_tag.entry(tag.clone()).or_default().entry(project.clone()).or_default() += seconds;
+        }
+    }
+
+    let mut tags: Vec<TagStat> = by_tag
+        .into_iter()
+        .map(|(tag, projects)| {
+            let mut projects: Vec<TagProjectStat> = projects
+                .into_iter()
+                .map(|(name, total_seconds)| TagProjectStat { name, total_seconds })
+                .collect();
+            projects.sort_by(|a, b| b.total_seconds.total_cmp(&a.total_seconds));
+            TagStat {
+                tag,
+                total_seconds: projects.iter().map(|p| p.total_seconds).sum(),
+                projects,
+            }
+        })
+        .collect();
+    tags.sort_by(|a, b| b.total_seconds.total_cmp(&a.total_seconds));
+
+    Ok(TagStatsResponse {
+        start_date,
+        end_date,
+        source,
+        tags,
+        untagged_seconds,
+    })
+}
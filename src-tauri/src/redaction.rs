@@ -0,0 +1,119 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::sync::Mutex;
+
+const REDACTED: &str = "[REDACTED]";
+const MIN_SECRET_LEN: usize = 8;
+
+static KNOWN_SECRETS: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+static PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        // Authorization headers and bearer tokens.
+        (r"(?i)\b(bearer|basic)\s+[A-Za-z0-9\-._~+/]+=*", "$1 [REDACTED]"),
+        // URL query strings and form bodies.
+        (
+            r"(?i)\b(code|state|access_token|refresh_token|id_token|token|api_key|code_verifier|code_challenge|client_secret|password)=[^&\s'\x22]+",
+            "$1=[REDACTED]",
+        ),
+        // JSON bodies.
+        (
+            r#"(?i)"(code|state|access_token|refresh_token|id_token|token|api_key|code_verifier|client_secret|password|email)"\s*:\s*"[^"]*""#,
+            r#""$1":"[REDACTED]""#,
+        ),
+        // Debug-formatted structs such as AuthState and PkceState.
+        (
+            r#"(?i)\b(access_token|refresh_token|api_key|code_verifier|state|code): Some\("[^"]*"\)"#,
+            r#"$1: Some("[REDACTED]")"#,
+        ),
+        (r#"(?i)\b(code_verifier|state): "[^"]*""#, r#"$1: "[REDACTED]""#),
+        // api_key = ... lines from ~/.wakatime.cfg.
+        (r"(?im)^(\s*api_key\s*=\s*)\S+", "${1}[REDACTED]"),
+        // WakaTime/Hackatime API keys. A bare UUID is usually just an id, so only mask it
+        // with the waka_ prefix or when it is the value of a key-like field.
+        (r"(?i)\bwaka_[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b", REDACTED),
+        (
+            r#"(?i)\b((?:x-)?(?:api[_-]?key|apikey|key|token|secret)["']?\s*[:=]\s*["']?)[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b"#,
+            "${1}[REDACTED]",
+        ),
+        (r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b", "[EMAIL]"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| (Regex::new(pattern).expect("invalid redaction pattern"), replacement))
+    .collect()
+});
+
+pub fn register_secret(secret: &str) {
+    let secret = secret.trim();
+    if secret.len() < MIN_SECRET_LEN {
+        return;
+    }
+
+    let mut secrets = KNOWN_SECRETS.lock().unwrap();
+    if !secrets.iter().any(|s| s == secret) {
+        secrets.push(secret.to_string());
+        // Replace longer secrets first so one that contains another is masked whole.
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+    }
+}
+
+// Called on logout so the next account's logs aren't masked with the previous one's secrets.
+pub fn clear_secrets() {
+    KNOWN_SECRETS.lock().unwrap().clear();
+}
+
+pub fn redact(text: &str) -> String {
+    let mut output = text.to_string();
+
+    {
+        let secrets = KNOWN_SECRETS.lock().unwrap();
+        for secret in secrets.iter() {
+            if output.contains(secret.as_str()) {
+                output = output.replace(secret.as_str(), REDACTED);
+            }
+        }
+    }
+
+    for (pattern, replacement) in PATTERNS.iter() {
+        if pattern.is_match(&output) {
+            output = pattern.replace_all(&output, *replacement).into_owned();
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bare_uuids_are_kept() {
+        let text = "Loaded heartbeat 123e4567-e89b-12d3-a456-426614174000 for project";
+        assert_eq!(redact(text), text);
+    }
+
+    #[test]
+    fn uuids_in_key_like_contexts_are_masked() {
+        let key = "123e4567-e89b-12d3-a456-426614174000";
+        for text in [
+            format!("waka_{}", key),
+            format!("api_key: {}", key),
+            format!("X-Api-Key={}", key),
+            format!("{{\"apiKey\": \"{}\"}}", key),
+        ] {
+            let redacted = redact(&text);
+            assert!(!redacted.contains(key), "{} was not redacted: {}", text, redacted);
+        }
+    }
+
+    #[test]
+    fn cleared_secrets_are_no_longer_masked() {
+        let _guard = crate::test_support::isolated();
+        register_secret("previous-account-token");
+        assert_eq!(redact("token previous-account-token"), "token [REDACTED]");
+
+        clear_secrets();
+        assert_eq!(redact("token previous-account-token"), "token previous-account-token");
+    }
+}
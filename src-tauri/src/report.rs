@@ -0,0 +1,652 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::activity::{dates_between, DateRange};
+use crate::auth::AuthState;
+use crate::breakdown::BreakdownSource;
+use crate::config::ApiConfig;
+use crate::database::{get_hackatime_config_dir, Database};
+use crate::export::{build_timesheet, ExportGrouping, ExportMode, TimesheetOptions};
+use crate::format::format_duration;
+use crate::html::{attribute, escape_html, tokenize, unescape_html, HtmlToken};
+use crate::push_log;
+
+const DEFAULT_TEMPLATE_NAME: &str = "default";
+const DEFAULT_TEMPLATE: &str = include_str!("../templates/report.html");
+
+const CHART_WIDTH: f64 = 640.0;
+const CHART_HEIGHT: f64 = 180.0;
+const CHART_LABEL_HEIGHT: f64 = 20.0;
+const CHART_COLOR: &str = "#ec3750";
+
+const PDF_PAGE_WIDTH: f64 = 595.0;
+const PDF_PAGE_HEIGHT: f64 = 842.0;
+const PDF_MARGIN: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Html,
+    Pdf,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyRate {
+    pub amount: f64,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportOptions {
+    #[serde(default, flatten)]
+    pub range: DateRange,
+    #[serde(default)]
+    pub projects: Vec<String>,
+    pub template: Option<String>,
+    pub rate: Option<HourlyRate>,
+    #[serde(default)]
+    pub allow_local_fallback: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportProject {
+    pub name: String,
+    pub display_name: String,
+    pub seconds: f64,
+    pub duration: String,
+    pub hours: String,
+    pub share: String,
+    pub amount: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDay {
+    pub date: String,
+    pub seconds: f64,
+    pub duration: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportContext {
+    pub title: String,
+    pub generated_at: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub source: BreakdownSource,
+    pub total_seconds: f64,
+    pub total_duration: String,
+    pub total_hours: String,
+    pub total_amount: Option<String>,
+    pub rate: Option<HourlyRate>,
+    pub projects: Vec<ReportProject>,
+    pub days: Vec<ReportDay>,
+    pub chart_svg: String,
+}
+
+fn format_amount(rate: &HourlyRate, seconds: f64) -> String {
+    format!("{} {:.2}", rate.currency, seconds / 3600.0 * rate.amount)
+}
+
+fn templates_dir() -> Result<PathBuf, String> {
+    let dir = get_hackatime_config_dir()?.join("templates");
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create templates directory: {}", e))?;
+    }
+    Ok(dir)
+}
+
+fn validate_template_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid template name '{}': use letters, digits, '-' and '_'", name))
+    }
+}
+
+// A user template with the same name takes precedence, so "default" can be customised too.
+fn load_template(name: &str) -> Result<String, String> {
+    validate_template_name(name)?;
+    let path = templates_dir()?.join(format!("{}.html", name));
+    if path.exists() {
+        return fs::read_to_string(&path).map_err(|e| format!("Failed to read template {}: {}", path.display(), e));
+    }
+    if name == DEFAULT_TEMPLATE_NAME {
+        return Ok(DEFAULT_TEMPLATE.to_string());
+    }
+    Err(format!("Report template '{}' not found", name))
+}
+
+fn render_chart_svg(days: &[ReportDay]) -> String {
+    let max = days.iter().map(|day| day.seconds).fold(0.0, f64::max);
+    let slot = CHART_WIDTH / days.len().max(1) as f64;
+    let bar_width = (slot * 0.7).max(1.0);
+    // Label every day for short ranges and thin them out for longer ones.
+    let label_every = (days.len() / 14).max(1);
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" role="img" aria-label="Daily coding time">"#,
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT + CHART_LABEL_HEIGHT
+    );
+    for (i, day) in days.iter().enumerate() {
+        let height = if max > 0.0 { day.seconds / max * CHART_HEIGHT } else { 0.0 };
+        let x = i as f64 * slot + (slot - bar_width) / 2.0;
+        svg.push_str(&format!(
+            r#"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" rx="2" fill="{}"><title>{}: {}</title></rect>"#,
+            x,
+            CHART_HEIGHT - height,
+            bar_width,
+            height,
+            CHART_COLOR,
+            escape_html(&day.date),
+            escape_html(&day.duration)
+        ));
+        if i % label_every == 0 {
+            svg.push_str(&format!(
+                r##"<text x="{:.1}" y="{:.1}" font-size="10" text-anchor="middle" fill="#8492a6">{}</text>"##,
+                x + bar_width / 2.0,
+                CHART_HEIGHT + 14.0,
+                escape_html(day.date.get(5..).unwrap_or(&day.date))
+            ));
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+async fn build_report_context(
+    options: ReportOptions,
+    api_config: &ApiConfig,
+    state: &Arc<tauri::async_runtime::Mutex<AuthState>>,
+) -> Result<ReportContext, String> {
+    let rate = options.rate;
+    let timesheet = build_timesheet(
+        &TimesheetOptions {
+            range: options.range,
+            mode: ExportMode::DailyTotals,
+            group_by: ExportGrouping::Project,
+            projects: options.projects,
+            allow_local_fallback: options.allow_local_fallback,
+            ..Default::default()
+        },
+        api_config,
+        state,
+    )
+    .await?;
+    let metadata = Database::new().await?.get_all_project_metadata().await?;
+
+    let mut daily: BTreeMap<String, f64> = dates_between(&timesheet.start_date, &timesheet.end_date)?
+        .into_iter()
+        .map(|date| (date, 0.0))
+        .collect();
+    for entry in timesheet.groups.iter().flat_map(|group| &group.entries) {
+        *daily.entry(entry.date.clone()).or_default() += entry.billable_seconds;
+    }
+
+    let total_seconds = timesheet.billable_seconds;
+    let mut report_projects: Vec<ReportProject> = timesheet
+        .groups
+        .iter()
+        .map(|group| ReportProject {
+            display_name: metadata
+                .get(&group.key)
+                .and_then(|m| m.alias.clone())
+                .unwrap_or_else(|| group.key.clone()),
+            name: group.key.clone(),
+            seconds: group.billable_seconds,
+            duration: format_duration(group.billable_seconds),
+            hours: format!("{:.2}", group.billable_seconds / 3600.0),
+            share: if total_seconds > 0.0 {
+                format!("{:.1}", group.billable_seconds / total_seconds * 100.0)
+            } else {
+                "0.0".to_string()
+            },
+            amount: rate.as_ref().map(|rate| format_amount(rate, group.billable_seconds)),
+        })
+        .collect();
+    report_projects.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+
+    let days: Vec<ReportDay> = daily
+        .into_iter()
+        .map(|(date, seconds)| ReportDay {
+            date,
+            seconds,
+            duration: format_duration(seconds),
+        })
+        .collect();
+
+    Ok(ReportContext {
+        title: "Coding time report".to_string(),
+        generated_at: chrono::Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        start_date: timesheet.start_date,
+        end_date: timesheet.end_date,
+        source: timesheet.source,
+        total_seconds,
+        total_duration: format_duration(total_seconds),
+        total_hours: format!("{:.2}", total_seconds / 3600.0),
+        total_amount: rate.as_ref().map(|rate| format_amount(rate, total_seconds)),
+        chart_svg: render_chart_svg(&days),
+        rate,
+        projects: report_projects,
+        days,
+    })
+}
+
+fn render_html(template: &str, context: &ReportContext) -> Result<String, String> {
+    let mut env = minijinja::Environment::new();
+    env.set_auto_escape_callback(|_| minijinja::AutoEscape::Html);
+    env.render_str(template, context)
+        .map_err(|e| format!("Failed to render report template: {}", e))
+}
+
+// Minimal single-font PDF writer: the built-in Helvetica needs no embedding, which keeps
+// reports small and avoids pulling in a full PDF stack for a short summary.
+struct PdfWriter {
+    pages: Vec<String>,
+    current: String,
+    y: f64,
+}
+
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            c if (c as u32) < 0x20 => " ".to_string(),
+            c if (c as u32) < 0x7f => c.to_string(),
+            c if (0xa0..=0xff).contains(&(c as u32)) => format!("\\{:03o}", c as u32),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+impl PdfWriter {
+    fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: String::new(),
+            y: PDF_PAGE_HEIGHT - PDF_MARGIN,
+        }
+    }
+
+    fn ensure_space(&mut self, height: f64) {
+        if self.y - height < PDF_MARGIN {
+            self.pages.push(std::mem::take(&mut self.current));
+            self.y = PDF_PAGE_HEIGHT - PDF_MARGIN;
+        }
+    }
+
+    fn text_at(&mut self, x: f64, text: &str, size: f64, bold: bool) {
+        self.current.push_str(&format!(
+            "BT /{} {} Tf {:.1} {:.1} Td ({}) Tj ET\n",
+            if bold { "F2" } else { "F1" },
+            size,
+            x,
+            self.y,
+            pdf_text(text)
+        ));
+    }
+
+    fn line(&mut self, text: &str, size: f64, bold: bool) {
+        self.ensure_space(size * 1.5);
+        self.y -= size * 1.5;
+        self.text_at(PDF_MARGIN, text, size, bold);
+    }
+
+    fn row(&mut self, columns: &[(f64, String)], bold: bool) {
+        self.ensure_space(16.0);
+        self.y -= 16.0;
+        for (x, text) in columns {
+            self.text_at(PDF_MARGIN + x, text, 10.0, bold);
+        }
+    }
+
+    fn table_row(&mut self, cells: &[(String, bool)]) {
+        if cells.is_empty() {
+            return;
+        }
+        let width = PDF_PAGE_WIDTH - PDF_MARGIN * 2.0;
+        let first_width = if cells.len() > 1 { width * 0.4 } else { width };
+        let other_width = (width - first_width) / (cells.len() - 1).max(1) as f64;
+
+        let mut x = 0.0;
+        let mut columns = Vec::with_capacity(cells.len());
+        for (i, (text, _)) in cells.iter().enumerate() {
+            let column_width = if i == 0 { first_width } else { other_width };
+            columns.push((x, text.chars().take(max_chars(column_width, 10.0)).collect()));
+            x += column_width;
+        }
+        self.row(&columns, cells.iter().all(|(_, header)| *header));
+    }
+
+    fn gap(&mut self, height: f64) {
+        self.y -= height;
+    }
+
+    fn bar_chart(&mut self, values: &[f64]) {
+        let height = 120.0;
+        let width = PDF_PAGE_WIDTH - PDF_MARGIN * 2.0;
+        self.ensure_space(height + 10.0);
+        self.y -= height;
+
+        let max = values.iter().copied().fold(0.0, f64::max);
+        let slot = width / values.len().max(1) as f64;
+        self.current.push_str("0.925 0.216 0.314 rg\n");
+        for (i, value) in values.iter().enumerate() {
+            if max <= 0.0 || *value <= 0.0 {
+                continue;
+            }
+            self.current.push_str(&format!(
+                "{:.1} {:.1} {:.1} {:.1} re f\n",
+                PDF_MARGIN + i as f64 * slot + slot * 0.15,
+                self.y,
+                (slot * 0.7).max(0.5),
+                value / max * height
+            ));
+        }
+        self.current.push_str("0 0 0 rg\n");
+        self.gap(10.0);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.pages.push(self.current);
+
+        let mut objects: Vec<String> = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            String::new(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_string(),
+        ];
+        let mut kids = Vec::new();
+        for content in &self.pages {
+            let page_id = objects.len() + 1;
+            kids.push(format!("{} 0 R", page_id));
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PDF_PAGE_WIDTH,
+                PDF_PAGE_HEIGHT,
+                page_id + 1
+            ));
+            objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+        }
+        objects[1] = format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), self.pages.len());
+
+        let mut output = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(output.len());
+            output.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref_offset = output.len();
+        output.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+        for offset in offsets {
+            output.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        output.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+        output.into_bytes()
+    }
+}
+
+// Helvetica averages about half an em per character.
+fn max_chars(width: f64, size: f64) -> usize {
+    (width / (size * 0.5)).max(1.0) as usize
+}
+
+fn wrap_text(text: &str, size: f64) -> Vec<String> {
+    let limit = max_chars(PDF_PAGE_WIDTH - PDF_MARGIN * 2.0, size);
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > limit {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn flush_text(pdf: &mut PdfWriter, text: &mut String, heading: Option<f64>) {
+    let size = heading.unwrap_or(11.0);
+    if heading.is_some() {
+        pdf.gap(6.0);
+    }
+    for line in wrap_text(text, size) {
+        pdf.line(&line, size, heading.is_some());
+    }
+    text.clear();
+}
+
+const PDF_SKIPPED_TAGS: &[&str] = &["head", "style", "script", "title"];
+const PDF_INLINE_TAGS: &[&str] = &["a", "abbr", "b", "code", "em", "i", "small", "span", "sub", "sup", "u"];
+const PDF_BLOCK_TAGS: &[&str] = &[
+    "article", "br", "caption", "div", "footer", "header", "hr", "li", "main", "ol", "p", "section", "table", "ul",
+];
+
+fn heading_size(tag: &str) -> Option<f64> {
+    match tag {
+        "h1" => Some(20.0),
+        "h2" => Some(14.0),
+        "h3" | "h4" | "h5" | "h6" => Some(12.0),
+        _ => None,
+    }
+}
+
+// The PDF is laid out from the rendered template, so edits to a user template show up in
+// both formats. CSS is not interpreted: headings, text blocks, table rows and the bars of
+// inline SVG charts are placed top to bottom in document order.
+fn render_pdf(html: &str) -> Vec<u8> {
+    let mut pdf = PdfWriter::new();
+    let mut text = String::new();
+    let mut heading: Option<f64> = None;
+    let mut skipping: Option<String> = None;
+    let mut cells: Vec<(String, bool)> = Vec::new();
+    let mut in_cell = false;
+    let mut bars: Option<Vec<f64>> = None;
+
+    for token in tokenize(html) {
+        if let Some(skipped) = &skipping {
+            if matches!(&token, HtmlToken::Close { name } if name == skipped) {
+                skipping = None;
+            }
+            continue;
+        }
+
+        match token {
+            HtmlToken::Text(raw) => {
+                if bars.is_none() {
+                    text.push_str(&unescape_html(raw));
+                }
+            }
+            HtmlToken::Open { name, attributes } => match name.as_str() {
+                tag if PDF_SKIPPED_TAGS.contains(&tag) => skipping = Some(name.clone()),
+                "svg" => {
+                    flush_text(&mut pdf, &mut text, heading);
+                    bars = Some(Vec::new());
+                }
+                "rect" => {
+                    if let Some(bars) = &mut bars {
+                        bars.push(attribute(attributes, "height").and_then(|h| h.parse().ok()).unwrap_or(0.0));
+                    }
+                }
+                "tr" => {
+                    flush_text(&mut pdf, &mut text, heading);
+                    cells.clear();
+                }
+                "td" | "th" => {
+                    text.clear();
+                    in_cell = true;
+                }
+                tag if heading_size(tag).is_some() => {
+                    flush_text(&mut pdf, &mut text, heading);
+                    heading = heading_size(tag);
+                }
+                tag if PDF_BLOCK_TAGS.contains(&tag) && !in_cell => flush_text(&mut pdf, &mut text, heading),
+                tag if PDF_INLINE_TAGS.contains(&tag) => {}
+                _ => text.push(' '),
+            },
+            HtmlToken::Close { name } => match name.as_str() {
+                "svg" => {
+                    if let Some(bars) = bars.take() {
+                        pdf.bar_chart(&bars);
+                    }
+                }
+                "td" | "th" => {
+                    cells.push((collapse_whitespace(&text), name == "th"));
+                    text.clear();
+                    in_cell = false;
+                }
+                "tr" => {
+                    pdf.table_row(&cells);
+                    cells.clear();
+                    text.clear();
+                }
+                tag if heading_size(tag).is_some() => {
+                    flush_text(&mut pdf, &mut text, heading);
+                    heading = None;
+                }
+                tag if PDF_BLOCK_TAGS.contains(&tag) && !in_cell => flush_text(&mut pdf, &mut text, heading),
+                tag if PDF_INLINE_TAGS.contains(&tag) => {}
+                _ => text.push(' '),
+            },
+        }
+    }
+
+    flush_text(&mut pdf, &mut text, heading);
+    pdf.finish()
+}
+
+#[tauri::command]
+pub async fn generate_report(
+    options: ReportOptions,
+    format: ReportFormat,
+    path: String,
+    api_config: ApiConfig,
+    state: State<'_, Arc<tauri::async_runtime::Mutex<AuthState>>>,
+) -> Result<String, String> {
+    if options.rate.as_ref().is_some_and(|rate| !rate.amount.is_finite() || rate.amount < 0.0) {
+        return Err("Hourly rate must be a positive number".to_string());
+    }
+
+    let template = load_template(options.template.as_deref().unwrap_or(DEFAULT_TEMPLATE_NAME))?;
+    let context = build_report_context(options, &api_config, state.inner()).await?;
+    let html = render_html(&template, &context)?;
+    let content = match format {
+        ReportFormat::Html => html.into_bytes(),
+        ReportFormat::Pdf => render_pdf(&html),
+    };
+
+    fs::write(&path, content).map_err(|e| format!("Failed to write report: {}", e))?;
+    push_log("info", "backend", format!("Report for {} to {} written to {}", context.start_date, context.end_date, path));
+    Ok(path)
+}
+
+#[tauri::command]
+pub fn list_report_templates() -> Result<Vec<String>, String> {
+    let mut names = vec![DEFAULT_TEMPLATE_NAME.to_string()];
+    let entries = fs::read_dir(templates_dir()?).map_err(|e| format!("Failed to read templates directory: {}", e))?;
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "html") {
+            if let Some(stem) = path.file_stem().map(|stem| stem.to_string_lossy().to_string()) {
+                if !names.contains(&stem) {
+                    names.push(stem);
+                }
+            }
+        }
+    }
+    names[1..].sort();
+    Ok(names)
+}
+
+// Seeds a user template from the bundled one so it can be edited in any text editor.
+#[tauri::command]
+pub fn create_report_template(name: String) -> Result<String, String> {
+    validate_template_name(&name)?;
+    let path = templates_dir()?.join(format!("{}.html", name));
+    if path.exists() {
+        return Err(format!("Template '{}' already exists at {}", name, path.display()));
+    }
+
+    fs::write(&path, DEFAULT_TEMPLATE).map_err(|e| format!("Failed to create template: {}", e))?;
+    push_log("info", "backend", format!("Created report template {}", path.display()));
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pdf_string(html: &str) -> String {
+        String::from_utf8_lossy(&render_pdf(html)).to_string()
+    }
+
+    #[test]
+    fn pdf_is_laid_out_from_the_rendered_template() {
+        let pdf = pdf_string(
+            r#"<html><head><title>Ignored</title><style>h1 { color: red; }</style></head><body>
+            <h1>Invoice for ACME</h1>
+            <svg><rect x="0" height="10"/><rect x="5" height='20'/><text>labels</text></svg>
+            <table><tr><th>Item</th><th>Hours</th></tr><tr><td>A &amp; B</td><td>2.00</td></tr></table>
+            <!-- <p>commented out</p> -->
+            <footer>Thanks, <b>ACME</b>!</footer></body></html>"#,
+        );
+
+        assert!(pdf.contains("(Invoice for ACME) Tj"));
+        assert!(pdf.contains("(Item) Tj"));
+        assert!(pdf.contains("(A & B) Tj"));
+        assert!(pdf.contains("(Thanks, ACME!) Tj"));
+        assert_eq!(pdf.matches(" re f").count(), 2);
+        assert!(!pdf.contains("Ignored") && !pdf.contains("color") && !pdf.contains("labels") && !pdf.contains("commented"));
+    }
+
+    #[test]
+    fn default_template_flags_local_data() {
+        let context = ReportContext {
+            title: "Coding time report".to_string(),
+            generated_at: "2024-03-04 10:00".to_string(),
+            start_date: "2024-03-04".to_string(),
+            end_date: "2024-03-04".to_string(),
+            source: BreakdownSource::Local,
+            total_seconds: 0.0,
+            total_duration: "0m".to_string(),
+            total_hours: "0.00".to_string(),
+            total_amount: None,
+            rate: None,
+            projects: Vec::new(),
+            days: Vec::new(),
+            chart_svg: String::new(),
+        };
+
+        let html = render_html(DEFAULT_TEMPLATE, &context).unwrap();
+        assert!(html.contains("class=\"note\""));
+        assert!(pdf_string(&html).contains("(Only includes time recorded by this app"));
+
+        let html = render_html(DEFAULT_TEMPLATE, &ReportContext { source: BreakdownSource::Server, ..context }).unwrap();
+        assert!(!html.contains("class=\"note\""));
+    }
+}
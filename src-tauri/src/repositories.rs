@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::database::Database;
+use crate::ini::IniDocument;
+use crate::push_log;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RepositoryInfo {
+    pub root: String,
+    pub name: String,
+    pub remote_url: Option<String>,
+    pub web_url: Option<String>,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IndexedRepository {
+    #[serde(flatten)]
+    pub repository: RepositoryInfo,
+    pub project: Option<String>,
+    pub last_seen: i64,
+}
+
+struct GitDirs {
+    // Holds HEAD; differs from common_dir for linked worktrees.
+    git_dir: PathBuf,
+    // Holds config and refs shared by every worktree.
+    common_dir: PathBuf,
+}
+
+fn read_git_dirs(dot_git: &Path) -> Option<GitDirs> {
+    if dot_git.is_dir() {
+        return Some(GitDirs {
+            git_dir: dot_git.to_path_buf(),
+            common_dir: dot_git.to_path_buf(),
+        });
+    }
+
+    // Worktrees and submodules use a `.git` file pointing at the real git directory.
+    let content = fs::read_to_string(dot_git).ok()?;
+    let target = content.lines().find_map(|line| line.strip_prefix("gitdir:"))?.trim();
+    let git_dir = dot_git.parent()?.join(target);
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .ok()
+        .map(|common| git_dir.join(common.trim()))
+        .unwrap_or_else(|| git_dir.clone());
+
+    Some(GitDirs { git_dir, common_dir })
+}
+
+fn find_git_root(path: &Path) -> Option<(PathBuf, GitDirs)> {
+    let start = if path.is_dir() { path } else { path.parent()? };
+    start.ancestors().find_map(|dir| {
+        let dot_git = dir.join(".git");
+        if dot_git.exists() {
+            read_git_dirs(&dot_git).map(|dirs| (dir.to_path_buf(), dirs))
+        } else {
+            None
+        }
+    })
+}
+
+fn read_branch(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref:") {
+        Some(reference) => {
+            let reference = reference.trim();
+            Some(reference.strip_prefix("refs/heads/").unwrap_or(reference).to_string())
+        }
+        // Detached HEAD: show the short commit like `git status` does.
+        None => head.get(..7).map(|sha| format!("({})", sha)),
+    }
+}
+
+fn read_remote_url(common_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(common_dir.join("config")).ok()?;
+    let document = IniDocument::parse_git_config(&content);
+
+    document.get("remote \"origin\"", "url").or_else(|| {
+        document
+            .sections()
+            .into_iter()
+            .filter(|section| section.starts_with("remote "))
+            .find_map(|section| document.get(&section, "url"))
+    })
+}
+
+// HTTPS remotes can carry a personal access token in the userinfo part; never keep it.
+fn strip_credentials(remote: &str) -> String {
+    match remote.split_once("://") {
+        Some((scheme, rest)) => {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host = authority.rsplit_once('@').map(|(_, host)| host).unwrap_or(authority);
+            if scheme == "ssh" {
+                format!("{}://{}/{}", scheme, authority, path)
+            } else {
+                format!("{}://{}/{}", scheme, host, path)
+            }
+        }
+        None => remote.to_string(),
+    }
+}
+
+pub fn remote_web_url(remote: &str) -> Option<String> {
+    let remote = remote.trim();
+
+    let (host, path) = if let Some(rest) = remote.strip_prefix("git@") {
+        rest.split_once(':')?
+    } else if let Some(rest) = remote
+        .strip_prefix("ssh://")
+        .or_else(|| remote.strip_prefix("https://"))
+        .or_else(|| remote.strip_prefix("http://"))
+        .or_else(|| remote.strip_prefix("git://"))
+    {
+        let rest = rest.rsplit_once('@').map(|(_, host)| host).unwrap_or(rest);
+        rest.split_once('/')?
+    } else {
+        return None;
+    };
+
+    let host = host.split(':').next().unwrap_or(host);
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("https://{}/{}", host, path))
+}
+
+pub fn resolve_repository(entity: &str) -> Option<RepositoryInfo> {
+    let path = Path::new(entity);
+    if !path.is_absolute() || !path.exists() {
+        return None;
+    }
+
+    let (root, dirs) = find_git_root(path)?;
+    let remote_url = read_remote_url(&dirs.common_dir).map(|remote| strip_credentials(&remote));
+
+    Some(RepositoryInfo {
+        name: root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        root: root.to_string_lossy().to_string(),
+        web_url: remote_url.as_deref().and_then(remote_web_url),
+        remote_url,
+        branch: read_branch(&dirs.git_dir),
+    })
+}
+
+async fn indexed_repository(root: &str) -> Result<IndexedRepository, String> {
+    let db = Database::new().await?;
+    db.list_repositories()
+        .await?
+        .into_iter()
+        .find(|indexed| indexed.repository.root == root)
+        .ok_or_else(|| format!("Repository {} is not in the index", root))
+}
+
+#[tauri::command]
+pub async fn list_repositories() -> Result<Vec<IndexedRepository>, String> {
+    let db = Database::new().await?;
+    db.list_repositories().await
+}
+
+#[tauri::command]
+pub async fn open_repository_folder(root: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    // Only open folders the index discovered, not arbitrary paths from the frontend.
+    let indexed = indexed_repository(&root).await?;
+    if !Path::new(&indexed.repository.root).is_dir() {
+        return Err(format!("Repository folder {} no longer exists", root));
+    }
+
+    app_handle
+        .opener()
+        .open_path(&indexed.repository.root, None::<&str>)
+        .map_err(|e| format!("Failed to open repository folder: {}", e))?;
+    push_log("info", "backend", format!("Opened repository folder {}", root));
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn open_repository_remote(root: String, app_handle: tauri::AppHandle) -> Result<(), String> {
+    use tauri_plugin_opener::OpenerExt;
+
+    let indexed = indexed_repository(&root).await?;
+    let web_url = indexed
+        .repository
+        .web_url
+        .ok_or_else(|| format!("Repository {} has no browsable remote", root))?;
+
+    app_handle
+        .opener()
+        .open_url(&web_url, None::<&str>)
+        .map_err(|e| format!("Failed to open repository remote: {}", e))?;
+    push_log("info", "backend", format!("Opened repository remote {}", web_url));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_is_read_from_a_tab_indented_git_config() {
+        let dir = std::env::temp_dir().join(format!("hackatime-git-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("config"),
+            "[core]\n\trepositoryformatversion = 0\n\tfilemode = true\n\tbare = false\n\tlogallrefupdates = true\n\
+             [remote \"origin\"]\n\turl = git@github.com:org/repo.git\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n\
+             [branch \"main\"]\n\tremote = origin\n\tmerge = refs/heads/main\n",
+        )
+        .unwrap();
+
+        let remote = read_remote_url(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(remote.as_deref(), Some("git@github.com:org/repo.git"));
+        assert_eq!(remote_web_url(&remote.unwrap()).as_deref(), Some("https://github.com/org/repo"));
+    }
+}
@@ -4,8 +4,10 @@ use tauri::State;
 
 use crate::auth::AuthState;
 use crate::config::ApiConfig;
+use crate::database::Database;
 use crate::discord_rpc::DiscordRpcService;
 use crate::push_log;
+use crate::repositories::{resolve_repository, RepositoryInfo};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HeartbeatData {
@@ -25,6 +27,10 @@ pub struct HeartbeatData {
     pub operating_system: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub machine: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<RepositoryInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +43,8 @@ pub struct SessionState {
     pub editor: Option<String>,
     pub language: Option<String>,
     pub entity: Option<String>,
+    #[serde(default)]
+    pub repository: Option<RepositoryInfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -113,7 +121,7 @@ pub async fn get_latest_heartbeat(
             format!("Failed to read response: {}", e)
         })?;
     
-    push_log("info", "backend", format!("Raw API response: {}", response_text));
+    push_log("debug", "backend", format!("Raw API response: {}", response_text));
 
     let heartbeat_data: Option<HeartbeatData> = if response_text.trim() == "null" || response_text.trim().is_empty() {
         None
@@ -123,7 +131,24 @@ pub async fn get_latest_heartbeat(
                 if data.timestamp == 0 {
                     data.timestamp = data.time as i64;
                 }
+                data.repository = data.entity.as_deref().and_then(resolve_repository);
+                if data.branch.is_none() {
+                    data.branch = data.repository.as_ref().and_then(|repo| repo.branch.clone());
+                }
                 push_log("info", "backend", format!("Successfully parsed heartbeat data: {:?}", data));
+                match Database::new().await {
+                    Ok(db) => {
+                        if let Err(e) = db.record_heartbeat(&data).await {
+                            push_log("warn", "backend", format!("Failed to record heartbeat locally: {}", e));
+                        }
+                        if let Some(repository) = &data.repository {
+                            if let Err(e) = db.upsert_repository(repository, data.project.as_deref(), data.timestamp).await {
+                                push_log("warn", "backend", format!("Failed to index repository: {}", e));
+                            }
+                        }
+                    }
+                    Err(e) => push_log("warn", "backend", format!("Failed to open database for heartbeat: {}", e)),
+                }
                 Some(data)
             }
             Err(e) => {
@@ -138,7 +163,7 @@ pub async fn get_latest_heartbeat(
     };
 
     
-    push_log("info", "backend", format!("Latest heartbeat response: {:?}", heartbeat_response));
+    push_log("debug", "backend", format!("Latest heartbeat response: {:?}", heartbeat_response));
     
     
     if let Some(heartbeat) = &heartbeat_response.heartbeat {
@@ -183,6 +208,7 @@ pub async fn get_latest_heartbeat(
             session.editor = None;
             session.language = None;
             session.entity = None;
+            session.repository = None;
 
             
             let mut discord_rpc = discord_rpc_state.lock().await;
@@ -200,6 +226,7 @@ pub async fn get_latest_heartbeat(
             session.editor = heartbeat.editor.clone();
             session.language = heartbeat.language.clone();
             session.entity = heartbeat.entity.clone();
+            session.repository = heartbeat.repository.clone();
 
             
             let mut discord_rpc = discord_rpc_state.lock().await;
@@ -218,6 +245,7 @@ pub async fn get_latest_heartbeat(
             session.editor = heartbeat.editor.clone();
             session.language = heartbeat.language.clone();
             session.entity = heartbeat.entity.clone();
+            session.repository = heartbeat.repository.clone();
 
             
             let mut discord_rpc = discord_rpc_state.lock().await;
@@ -240,6 +268,7 @@ pub async fn get_latest_heartbeat(
             session.editor = None;
             session.language = None;
             session.entity = None;
+            session.repository = None;
 
             
             let mut discord_rpc = discord_rpc_state.lock().await;
@@ -261,6 +290,7 @@ pub async fn get_latest_heartbeat(
             session.editor = None;
             session.language = None;
             session.entity = None;
+            session.repository = None;
 
             
             let mut discord_rpc = discord_rpc_state.lock().await;
@@ -1,7 +1,11 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
 use serde::{Serialize, Deserialize};
 
+use crate::ini::IniDocument;
+use crate::push_log;
+
 #[derive(Serialize, Deserialize)]
 pub struct WakatimeConfigCheck {
     pub exists: bool,
@@ -11,7 +15,16 @@ pub struct WakatimeConfigCheck {
     pub config_path: String,
 }
 
-fn get_wakatime_config_path() -> Result<String, String> {
+pub fn get_wakatime_config_path() -> Result<String, String> {
+    if let Ok(wakatime_home) = std::env::var("WAKATIME_HOME") {
+        if !wakatime_home.trim().is_empty() {
+            return Ok(Path::new(wakatime_home.trim())
+                .join(".wakatime.cfg")
+                .to_string_lossy()
+                .to_string());
+        }
+    }
+
     #[cfg(target_os = "windows")]
     {
         let userprofile = std::env::var("USERPROFILE")
@@ -27,6 +40,139 @@ fn get_wakatime_config_path() -> Result<String, String> {
     }
 }
 
+const BACKUP_KEEP_COUNT: usize = 5;
+
+pub fn load_wakatime_document(config_path: &str) -> Result<IniDocument, String> {
+    if !Path::new(config_path).exists() {
+        return Ok(IniDocument::parse(""));
+    }
+
+    let content = fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read config file: {}", e))?;
+    Ok(IniDocument::parse(&content))
+}
+
+pub fn backup_wakatime_config(config_path: &str) -> Result<Option<String>, String> {
+    if !Path::new(config_path).exists() {
+        return Ok(None);
+    }
+
+    // Millisecond timestamps plus a counter keep two writes in the same instant from
+    // overwriting each other's backup.
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S%3f");
+    let mut backup_path = format!("{}.bak.{}", config_path, timestamp);
+    let mut attempt = 1;
+    while Path::new(&backup_path).exists() {
+        backup_path = format!("{}.bak.{}-{:03}", config_path, timestamp, attempt);
+        attempt += 1;
+    }
+    fs::copy(config_path, &backup_path)
+        .map_err(|e| format!("Failed to backup existing config: {}", e))?;
+
+    prune_wakatime_backups(config_path);
+    Ok(Some(backup_path))
+}
+
+fn prune_wakatime_backups(config_path: &str) {
+    let path = Path::new(config_path);
+    let (Some(dir), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return;
+    };
+    let prefix = format!("{}.bak.", file_name.to_string_lossy());
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut backups: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy().starts_with(&prefix))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    // Timestamps sort lexically, so the oldest backups come first.
+    backups.sort();
+    if backups.len() > BACKUP_KEEP_COUNT {
+        for old in &backups[..backups.len() - BACKUP_KEEP_COUNT] {
+            if let Err(e) = fs::remove_file(old) {
+                push_log("warn", "backend", format!("Failed to remove old config backup {}: {}", old.display(), e));
+            }
+        }
+    }
+}
+
+pub fn write_wakatime_document(config_path: &str, document: &IniDocument) -> Result<(), String> {
+    // Write next to the target and rename over it so a crash never leaves a truncated config.
+    let temp_path = format!("{}.tmp", config_path);
+    let mut file = fs::File::create(&temp_path)
+        .map_err(|e| format!("Failed to create temporary config file: {}", e))?;
+    file.write_all(document.render().as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| {
+            let _ = fs::remove_file(&temp_path);
+            format!("Failed to write config file: {}", e)
+        })?;
+    drop(file);
+
+    fs::rename(&temp_path, config_path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to replace config file: {}", e)
+    })
+}
+
+pub fn get_wakatime_setting(key: &str) -> Result<Option<String>, String> {
+    let config_path = get_wakatime_config_path()?;
+    let document = load_wakatime_document(&config_path)?;
+    Ok(document.get("settings", key))
+}
+
+pub fn set_wakatime_setting(key: &str, value: Option<&str>) -> Result<(), String> {
+    let config_path = get_wakatime_config_path()?;
+    let mut document = load_wakatime_document(&config_path)?;
+
+    match value {
+        Some(value) => document.set("settings", key, value),
+        None => {
+            if !document.remove("settings", key) {
+                return Ok(());
+            }
+        }
+    }
+
+    write_wakatime_document(&config_path, &document)
+}
+
+fn merge_hackatime_settings(config_path: &str, api_key: &str, api_url: &str, newline: &'static str) -> Result<String, String> {
+    crate::redaction::register_secret(api_key);
+    let existed = Path::new(config_path).exists();
+    let mut document = load_wakatime_document(config_path)?;
+    if !existed {
+        document.set_newline(newline);
+    }
+
+    document.set("settings", "api_url", api_url);
+    document.set("settings", "api_key", api_key);
+    document.set("settings", "heartbeat_rate_limit_seconds", "30");
+
+    if let Some(backup_path) = backup_wakatime_config(config_path)? {
+        push_log("info", "backend", format!("Backed up existing config to {}", backup_path));
+    }
+
+    write_wakatime_document(config_path, &document)?;
+
+    let config_content = fs::read_to_string(config_path)
+        .map_err(|e| format!("Failed to read config file: {}", e))?;
+
+    if !check_config_has_required_values(&config_content, api_key, api_url) {
+        return Err("Config file is missing required api_url and api_key values".to_string());
+    }
+
+    Ok(config_content)
+}
+
 fn get_expected_config_content(api_key: &str, api_url: &str) -> String {
     #[cfg(target_os = "windows")]
     {
@@ -46,31 +192,9 @@ fn get_expected_config_content(api_key: &str, api_url: &str) -> String {
 }
 
 fn check_config_has_required_values(content: &str, api_key: &str, api_url: &str) -> bool {
-    let normalized = content.replace("\r\n", "\n");
-    let mut found_api_url = false;
-    let mut found_api_key = false;
-    
-    for line in normalized.lines() {
-        let trimmed = line.trim();
-        
-        if trimmed.starts_with("api_url") {
-            if let Some(value) = trimmed.split('=').nth(1) {
-                let value = value.trim();
-                if value == api_url {
-                    found_api_url = true;
-                }
-            }
-        } else if trimmed.starts_with("api_key") {
-            if let Some(value) = trimmed.split('=').nth(1) {
-                let value = value.trim();
-                if value == api_key {
-                    found_api_key = true;
-                }
-            }
-        }
-    }
-    
-    found_api_url && found_api_key
+    let document = IniDocument::parse(content);
+    document.get("settings", "api_url").as_deref() == Some(api_url)
+        && document.get("settings", "api_key").as_deref() == Some(api_key)
 }
 
 #[tauri::command]
@@ -106,56 +230,18 @@ pub async fn check_wakatime_config(api_key: String, api_url: String) -> Result<W
 #[tauri::command]
 pub async fn apply_wakatime_config(api_key: String, api_url: String) -> Result<String, String> {
     let config_path = get_wakatime_config_path()?;
-    let backup_path = format!("{}.bak", config_path);
-    
-    
-    if Path::new(&config_path).exists() {
-        if let Err(e) = fs::copy(&config_path, &backup_path) {
-            return Err(format!("Failed to backup existing config: {}", e));
-        }
-    }
-    
-    let config_content = get_expected_config_content(&api_key, &api_url);
-    
-    if let Err(e) = fs::write(&config_path, &config_content) {
-        return Err(format!("Failed to write config file: {}", e));
-    }
-    
+    let newline = if cfg!(target_os = "windows") { "\r\n" } else { "\n" };
+
+    merge_hackatime_settings(&config_path, &api_key, &api_url, newline)?;
+
     Ok(format!("Config file successfully written to {}", config_path))
 }
 
 #[tauri::command]
 pub async fn setup_hackatime_macos_linux(api_key: String, api_url: String) -> Result<String, String> {
-    let home_dir = std::env::var("HOME").map_err(|_| "Failed to get home directory")?;
-
-    let config_path = format!("{}/.wakatime.cfg", home_dir);
-    let backup_path = format!("{}/.wakatime.cfg.bak", home_dir);
-
-    if Path::new(&config_path).exists() {
-        if let Err(e) = fs::rename(&config_path, &backup_path) {
-            return Err(format!("Failed to backup existing config: {}", e));
-        }
-    }
-
-    let config_content = format!(
-        "[settings]\napi_url = {}\napi_key = {}\nheartbeat_rate_limit_seconds = 30\n",
-        api_url, api_key
-    );
-
-    if let Err(e) = fs::write(&config_path, config_content) {
-        return Err(format!("Failed to write config file: {}", e));
-    }
-
-    if !Path::new(&config_path).exists() {
-        return Err("Config file was not created".to_string());
-    }
+    let config_path = get_wakatime_config_path()?;
 
-    let config_content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config file: {}", e))?;
-
-    if !check_config_has_required_values(&config_content, &api_key, &api_url) {
-        return Err("Config file is missing required api_url and api_key values".to_string());
-    }
+    merge_hackatime_settings(&config_path, &api_key, &api_url, "\n")?;
 
     Ok(format!(
         "Config file created successfully at {}",
@@ -165,37 +251,9 @@ pub async fn setup_hackatime_macos_linux(api_key: String, api_url: String) -> Re
 
 #[tauri::command]
 pub async fn setup_hackatime_windows(api_key: String, api_url: String) -> Result<String, String> {
-    let userprofile =
-        std::env::var("USERPROFILE").map_err(|_| "Failed to get USERPROFILE directory")?;
-
-    let config_path = format!("{}\\.wakatime.cfg", userprofile);
-    let backup_path = format!("{}\\.wakatime.cfg.bak", userprofile);
-
-    if Path::new(&config_path).exists() {
-        if let Err(e) = fs::rename(&config_path, &backup_path) {
-            return Err(format!("Failed to backup existing config: {}", e));
-        }
-    }
-
-    let config_content = format!(
-        "[settings]\r\napi_url = {}\r\napi_key = {}\r\nheartbeat_rate_limit_seconds = 30\r\n",
-        api_url, api_key
-    );
-
-    if let Err(e) = fs::write(&config_path, config_content) {
-        return Err(format!("Failed to write config file: {}", e));
-    }
-
-    if !Path::new(&config_path).exists() {
-        return Err("Config file was not created".to_string());
-    }
+    let config_path = get_wakatime_config_path()?;
 
-    let config_content = fs::read_to_string(&config_path)
-        .map_err(|e| format!("Failed to read config file: {}", e))?;
-
-    if !check_config_has_required_values(&config_content, &api_key, &api_url) {
-        return Err("Config file is missing required api_url and api_key values".to_string());
-    }
+    merge_hackatime_settings(&config_path, &api_key, &api_url, "\r\n")?;
 
     Ok(format!(
         "Config file created successfully at {}",
@@ -248,3 +306,26 @@ pub async fn setup_hackatime_complete(api_key: String, api_url: String) -> Resul
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backups_in_the_same_instant_do_not_overwrite_each_other() {
+        let dir = std::env::temp_dir().join(format!("hackatime-backup-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let config_path = dir.join(".wakatime.cfg").to_string_lossy().to_string();
+
+        fs::write(&config_path, "first").unwrap();
+        let first = backup_wakatime_config(&config_path).unwrap().unwrap();
+        fs::write(&config_path, "second").unwrap();
+        let second = backup_wakatime_config(&config_path).unwrap().unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "first");
+        assert_eq!(fs::read_to_string(&second).unwrap(), "second");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -6,6 +6,9 @@ use chrono::Datelike;
 use crate::auth::AuthState;
 use crate::config::ApiConfig;
 use crate::database::Database;
+use crate::format::format_duration;
+use crate::i18n::{t, t_with};
+use crate::insights::{evaluate, load_rules, RuleSet, Severity, StatsContext};
 use crate::push_log;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -18,6 +21,10 @@ pub struct StatisticsData {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TrendStatistic {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub severity: Severity,
     pub title: String,
     pub value: String,
     pub change: String,
@@ -39,6 +46,10 @@ pub struct ChartData {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Insight {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub severity: Severity,
     pub title: String,
     pub description: String,
     pub value: String,
@@ -56,7 +67,7 @@ pub struct ProgrammerClass {
     pub color: String,
 }
 
-async fn fetch_hours_with_cache(
+pub async fn fetch_hours_with_cache(
     client: &reqwest::Client,
     base_url: &str,
     access_token: &str,
@@ -90,7 +101,6 @@ async fn fetch_hours_with_cache(
     );
     
     push_log("info", "backend", format!("API REQUEST: GET {}", url));
-    push_log("debug", "backend", format!("Authorization: Bearer {}...", &access_token[..20.min(access_token.len())]));
     
     let response = client
         .get(&url)
@@ -135,7 +145,7 @@ async fn fetch_hours_with_cache(
     Ok(data)
 }
 
-async fn fetch_streak_with_cache(
+pub(crate) async fn fetch_streak_with_cache(
     client: &reqwest::Client,
     base_url: &str,
     access_token: &str,
@@ -143,7 +153,6 @@ async fn fetch_streak_with_cache(
     let url = format!("{}/api/v1/authenticated/streak", base_url);
     
     push_log("info", "backend", format!("API REQUEST: GET {}", url));
-    push_log("debug", "backend", format!("Authorization: Bearer {}...", &access_token[..20.min(access_token.len())]));
     
     let response = client
         .get(&url)
@@ -217,39 +226,25 @@ pub async fn get_statistics_data(
                 let seconds = day_data["total_seconds"].as_u64().unwrap_or(0);
                 total_seconds += seconds;
                 
-                let day_name = match date.weekday() {
-                    chrono::Weekday::Mon => "Mon",
-                    chrono::Weekday::Tue => "Tue",
-                    chrono::Weekday::Wed => "Wed",
-                    chrono::Weekday::Thu => "Thu",
-                    chrono::Weekday::Fri => "Fri",
-                    chrono::Weekday::Sat => "Sat",
-                    chrono::Weekday::Sun => "Sun",
-                };
+                let day_name = crate::i18n::weekday_short(date.weekday());
                 
                 daily_hours.insert(date_str.clone(), serde_json::json!({
                     "date": date_str,
                     "day_name": day_name,
                     "hours": seconds as f64 / 3600.0,
-                    "seconds": seconds
+                    "seconds": seconds,
+                    "duration": format_duration(seconds as f64)
                 }));
             }
             Err(_) => {
-                let day_name = match date.weekday() {
-                    chrono::Weekday::Mon => "Mon",
-                    chrono::Weekday::Tue => "Tue",
-                    chrono::Weekday::Wed => "Wed",
-                    chrono::Weekday::Thu => "Thu",
-                    chrono::Weekday::Fri => "Fri",
-                    chrono::Weekday::Sat => "Sat",
-                    chrono::Weekday::Sun => "Sun",
-                };
+                let day_name = crate::i18n::weekday_short(date.weekday());
                 
                 daily_hours.insert(date_str.clone(), serde_json::json!({
                     "date": date_str,
                     "day_name": day_name,
                     "hours": 0.0,
-                    "seconds": 0
+                    "seconds": 0,
+                    "duration": format_duration(0.0)
                 }));
             }
         }
@@ -295,10 +290,12 @@ pub async fn get_statistics_data(
     let hours_data = serde_json::json!({
         "weekly_stats": {
             "time_coded_seconds": total_seconds,
+            "time_coded": format_duration(total_seconds as f64),
             "daily_hours": daily_hours
         },
         "all_time_stats": {
-            "time_coded_seconds": all_time_seconds
+            "time_coded_seconds": all_time_seconds,
+            "time_coded": format_duration(all_time_seconds as f64)
         },
         "calculated_metrics": {
             "prev_week_hours": (prev_week_seconds as f64 / 3600.0 * 10.0).round() / 10.0,
@@ -351,7 +348,7 @@ pub async fn get_dashboard_stats(
         .as_ref()
         .ok_or("No access token available")?;
     
-    push_log("debug", "backend", format!("Access token present: {}...", &access_token[..20.min(access_token.len())]));
+    push_log("debug", "backend", "Access token present".to_string());
 
     let client = reqwest::Client::new();
 
@@ -409,41 +406,27 @@ pub async fn get_dashboard_stats(
                 push_log("info", "backend", format!(" {} ({} days ago): {} seconds ({:.2} hours)", 
                     date_str, days_ago, seconds, seconds as f64 / 3600.0));
                 
-                let day_name = match date.weekday() {
-                    chrono::Weekday::Mon => "Mon",
-                    chrono::Weekday::Tue => "Tue",
-                    chrono::Weekday::Wed => "Wed",
-                    chrono::Weekday::Thu => "Thu",
-                    chrono::Weekday::Fri => "Fri",
-                    chrono::Weekday::Sat => "Sat",
-                    chrono::Weekday::Sun => "Sun",
-                };
+                let day_name = crate::i18n::weekday_short(date.weekday());
                 
                 daily_hours.insert(date_str.clone(), serde_json::json!({
                     "date": date_str,
                     "day_name": day_name,
                     "hours": seconds as f64 / 3600.0,
-                    "seconds": seconds
+                    "seconds": seconds,
+                    "duration": format_duration(seconds as f64)
                 }));
             }
             Err(e) => {
                 push_log("warn", "backend", format!("Failed to fetch hours for {}: {}", date_str, e));
                 
-                let day_name = match date.weekday() {
-                    chrono::Weekday::Mon => "Mon",
-                    chrono::Weekday::Tue => "Tue",
-                    chrono::Weekday::Wed => "Wed",
-                    chrono::Weekday::Thu => "Thu",
-                    chrono::Weekday::Fri => "Fri",
-                    chrono::Weekday::Sat => "Sat",
-                    chrono::Weekday::Sun => "Sun",
-                };
+                let day_name = crate::i18n::weekday_short(date.weekday());
                 
                 daily_hours.insert(date_str.clone(), serde_json::json!({
                     "date": date_str,
                     "day_name": day_name,
                     "hours": 0.0,
-                    "seconds": 0
+                    "seconds": 0,
+                    "duration": format_duration(0.0)
                 }));
             }
         }
@@ -494,6 +477,7 @@ pub async fn get_dashboard_stats(
         "longest_streak": longest_streak,
         "weekly_stats": {
             "time_coded_seconds": total_seconds,
+            "time_coded": format_duration(total_seconds as f64),
             "daily_hours": daily_hours
         },
         "calculated_metrics": {
@@ -510,7 +494,7 @@ pub async fn get_dashboard_stats(
     Ok(result)
 }
 
-async fn process_statistics_data(
+pub(crate) async fn process_statistics_data(
     dashboard_stats: serde_json::Value,
 ) -> Result<StatisticsData, String> {
     
@@ -535,14 +519,16 @@ async fn process_statistics_data(
     push_log("info", "backend", format!("[process_statistics_data] Current week: {} seconds ({:.2}h)", 
         weekly_time, weekly_time / 3600.0));
 
-    
-    let trends = calculate_trends(weekly_time, prev_week_time, current_streak).await;
+    let context = StatsContext::new(weekly_time, prev_week_time, all_time_time, current_streak);
+    let rules = load_rules();
+
+    let trends = calculate_trends(&context, &rules);
 
     
     let charts = generate_chart_data(&dashboard_stats).await?;
 
     
-    let insights = generate_insights(weekly_time, all_time_time, current_streak).await;
+    let insights = generate_insights(&context, &rules);
 
     
     let programmer_class = analyze_programmer_class(&dashboard_stats).await;
@@ -555,145 +541,26 @@ async fn process_statistics_data(
     })
 }
 
-async fn calculate_trends(weekly_time: f64, prev_week_time: f64, current_streak: u64) -> Vec<TrendStatistic> {
-    let mut trends = Vec::new();
-
-    
-    
-    let last_week_time = prev_week_time;
-    let last_week_streak = if current_streak > 0 {
-        current_streak - 1
-    } else {
-        0
-    };
-
-    
-    let time_change = if last_week_time > 0.0 {
-        let change = ((weekly_time - last_week_time) / last_week_time * 100.0).round() as i32;
-        push_log("info", "backend", format!("[calculate_trends] Weekly change: {:.2}h -> {:.2}h = {}%", 
-            last_week_time / 3600.0, weekly_time / 3600.0, change));
-        change
-    } else if weekly_time > 0.0 {
-        push_log("info", "backend", "[calculate_trends] No previous week data, defaulting to +100%".to_string());
-        100 
-    } else {
-        push_log("info", "backend", "[calculate_trends] No data for either week, 0%".to_string());
-        0
-    };
-    let time_trend = if time_change > 0 {
-        TrendStatistic {
-            title: "Weekly Coding Time".to_string(),
-            value: format!("{:.1}h", weekly_time / 3600.0),
-            change: format!("+{}%", time_change),
-            change_type: "increase".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#4CAF50".to_string(),
-        }
-    } else if time_change < 0 {
-        TrendStatistic {
-            title: "Weekly Coding Time".to_string(),
-            value: format!("{:.1}h", weekly_time / 3600.0),
-            change: format!("{}%", time_change),
-            change_type: "decrease".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#F44336".to_string(),
-        }
-    } else {
-        TrendStatistic {
-            title: "Weekly Coding Time".to_string(),
-            value: format!("{:.1}h", weekly_time / 3600.0),
-            change: "No change".to_string(),
-            change_type: "neutral".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#FF9800".to_string(),
-        }
-    };
-    trends.push(time_trend);
-
-    
-    let streak_change = current_streak as i32 - last_week_streak as i32;
-    let streak_trend = if streak_change > 0 {
-        TrendStatistic {
-            title: "Coding Streak".to_string(),
-            value: format!("{} days", current_streak),
-            change: format!("+{} days", streak_change),
-            change_type: "increase".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#FF5722".to_string(),
-        }
-    } else if streak_change < 0 {
-        TrendStatistic {
-            title: "Coding Streak".to_string(),
-            value: format!("{} days", current_streak),
-            change: format!("{} days", streak_change),
-            change_type: "decrease".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#F44336".to_string(),
-        }
-    } else {
-        TrendStatistic {
-            title: "Coding Streak".to_string(),
-            value: format!("{} days", current_streak),
-            change: "Maintained".to_string(),
-            change_type: "neutral".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#FF9800".to_string(),
-        }
-    };
-    trends.push(streak_trend);
-
-    
-    let daily_average = weekly_time / 3600.0 / 7.0;
-    let last_week_daily = last_week_time / 3600.0 / 7.0;  
-    
-    let focus_change = if last_week_daily > 0.0 {
-        ((daily_average - last_week_daily) / last_week_daily * 100.0).round() as i32
-    } else if daily_average > 0.0 {
-        100  
-    } else {
-        0
-    };
-
-    let focus_trend = if focus_change > 0 {
-        TrendStatistic {
-            title: "Daily Focus Time".to_string(),
-            value: format!("{:.1}h/day", daily_average),
-            change: format!("+{}%", focus_change),
-            change_type: "increase".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#4CAF50".to_string(),
-        }
-    } else if focus_change < 0 {
-        TrendStatistic {
-            title: "Daily Focus Time".to_string(),
-            value: format!("{:.1}h/day", daily_average),
-            change: format!("{}%", focus_change),
-            change_type: "decrease".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#F44336".to_string(),
-        }
-    } else {
-        TrendStatistic {
-            title: "Daily Focus Time".to_string(),
-            value: format!("{:.1}h/day", daily_average),
-            change: "No change".to_string(),
-            change_type: "neutral".to_string(),
-            period: "vs last week".to_string(),
-            icon: "".to_string(),
-            color: "#FF9800".to_string(),
-        }
-    };
-    trends.push(focus_trend);
-
-    trends
+fn calculate_trends(context: &StatsContext, rules: &RuleSet) -> Vec<TrendStatistic> {
+    push_log("debug", "backend", format!(
+        "[calculate_trends] Weekly change: {:.2}h -> {:.2}h = {}%",
+        context.prev_week_hours, context.weekly_hours, context.weekly_change_percent
+    ));
+
+    evaluate(&rules.trends, context)
+        .into_iter()
+        .map(|matched| TrendStatistic {
+            id: matched.id,
+            severity: matched.severity,
+            title: matched.title,
+            value: matched.value,
+            change: matched.change,
+            change_type: matched.change_type,
+            period: matched.period,
+            icon: matched.icon,
+            color: matched.color,
+        })
+        .collect()
 }
 
 async fn generate_chart_data(
@@ -714,34 +581,42 @@ async fn generate_chart_data(
     }
     
     if chart_data.is_empty() {
-        let day_names = vec!["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
-        for day in day_names {
-            labels.push(day.to_string());
+        let weekdays = [
+            chrono::Weekday::Mon,
+            chrono::Weekday::Tue,
+            chrono::Weekday::Wed,
+            chrono::Weekday::Thu,
+            chrono::Weekday::Fri,
+            chrono::Weekday::Sat,
+            chrono::Weekday::Sun,
+        ];
+        for weekday in weekdays {
+            labels.push(crate::i18n::weekday_short(weekday));
             chart_data.push(0.0);
         }
     }
 
     charts.push(ChartData {
         id: "daily_hours".to_string(),
-        title: "Daily Coding Hours".to_string(),
+        title: t("stats.daily_hours"),
         chart_type: "bar".to_string(),
         data: serde_json::json!({
             "labels": labels,
             "datasets": [{
-                "label": "Hours",
+                "label": t("stats.hours"),
                 "data": chart_data,
                 "backgroundColor": "#FB4B20",
                 "borderColor": "#FB4B20",
                 "borderWidth": 1
             }]
         }),
-        period: "Last 7 days".to_string(),
+        period: t("stats.period.last_7_days"),
         color_scheme: "orange".to_string(),
     });
 
     
     if let Some(top_language) = dashboard_stats["weekly_stats"]["top_language"].as_object() {
-        let language_name = top_language["name"].as_str().unwrap_or("Unknown");
+        let language_name = top_language["name"].as_str().map(str::to_string).unwrap_or_else(|| t("stats.unknown"));
         let language_seconds = top_language["seconds"].as_u64().unwrap_or(0) as f64;
         let total_seconds = dashboard_stats["weekly_stats"]["time_coded_seconds"]
             .as_u64()
@@ -750,17 +625,17 @@ async fn generate_chart_data(
 
         charts.push(ChartData {
             id: "language_distribution".to_string(),
-            title: "Top Language".to_string(),
+            title: t("stats.top_language"),
             chart_type: "doughnut".to_string(),
             data: serde_json::json!({
-                "labels": [language_name, "Others"],
+                "labels": [language_name, t("stats.others")],
                 "datasets": [{
                     "data": [percentage, 100 - percentage],
                     "backgroundColor": ["#FB4B20", "#E0E0E0"],
                     "borderWidth": 0
                 }]
             }),
-            period: "This week".to_string(),
+            period: t("stats.period.this_week"),
             color_scheme: "orange".to_string(),
         });
     }
@@ -784,17 +659,17 @@ async fn generate_chart_data(
         };
 
         trend_data.push(week_hours);
-        trend_labels.push(format!("Week {}", 4 - week));
+        trend_labels.push(t_with("stats.week", &[("number", (4 - week).to_string())]));
     }
 
     charts.push(ChartData {
         id: "weekly_trend".to_string(),
-        title: "Weekly Trend".to_string(),
+        title: t("stats.weekly_trend"),
         chart_type: "line".to_string(),
         data: serde_json::json!({
             "labels": trend_labels,
             "datasets": [{
-                "label": "Hours",
+                "label": t("stats.hours"),
                 "data": trend_data,
                 "borderColor": "#FB4B20",
                 "backgroundColor": "rgba(251, 75, 32, 0.1)",
@@ -802,135 +677,41 @@ async fn generate_chart_data(
                 "tension": 0.4
             }]
         }),
-        period: "Last 4 weeks".to_string(),
+        period: t("stats.period.last_4_weeks"),
         color_scheme: "orange".to_string(),
     });
 
     Ok(charts)
 }
 
-async fn generate_insights(
-    weekly_time: f64,
-    all_time_time: f64,
-    current_streak: u64,
-) -> Vec<Insight> {
-    let mut insights = Vec::new();
-
-    
-    let daily_average = weekly_time / 3600.0 / 7.0;
-    let consistency_insight = if daily_average >= 2.0 {
-        Insight {
-            title: "Consistent Coder".to_string(),
-            description: "You've been coding consistently every day this week!".to_string(),
-            value: format!("{:.1}h/day", daily_average),
-            trend: "Great consistency".to_string(),
-            icon: "".to_string(),
-            color: "#4CAF50".to_string(),
-        }
-    } else if daily_average >= 1.0 {
-        Insight {
-            title: "Steady Progress".to_string(),
-            description: "You're maintaining a good coding rhythm.".to_string(),
-            value: format!("{:.1}h/day", daily_average),
-            trend: "Keep it up".to_string(),
-            icon: "".to_string(),
-            color: "#FF9800".to_string(),
-        }
-    } else {
-        Insight {
-            title: "Room for Growth".to_string(),
-            description: "Try to code a bit more each day to build momentum.".to_string(),
-            value: format!("{:.1}h/day", daily_average),
-            trend: "Build momentum".to_string(),
-            icon: "".to_string(),
-            color: "#2196F3".to_string(),
-        }
-    };
-    insights.push(consistency_insight);
-
-    
-    let streak_insight = if current_streak >= 30 {
-        Insight {
-            title: "Streak Master".to_string(),
-            description: "Incredible! You've been coding for over a month straight!".to_string(),
-            value: format!("{} days", current_streak),
-            trend: "Amazing dedication".to_string(),
-            icon: "".to_string(),
-            color: "#FFD700".to_string(),
-        }
-    } else if current_streak >= 7 {
-        Insight {
-            title: "Week Warrior".to_string(),
-            description: "You've been coding for a full week! Great job!".to_string(),
-            value: format!("{} days", current_streak),
-            trend: "Excellent progress".to_string(),
-            icon: "".to_string(),
-            color: "#FF5722".to_string(),
-        }
-    } else if current_streak > 0 {
-        Insight {
-            title: "Getting Started".to_string(),
-            description: "You're building a coding habit! Keep it going!".to_string(),
-            value: format!("{} days", current_streak),
-            trend: "Building momentum".to_string(),
-            icon: "".to_string(),
-            color: "#4CAF50".to_string(),
-        }
-    } else {
-        Insight {
-            title: "Fresh Start".to_string(),
-            description: "Ready to start your coding journey? Let's begin!".to_string(),
-            value: "0 days".to_string(),
-            trend: "Start today".to_string(),
-            icon: "".to_string(),
-            color: "#9C27B0".to_string(),
-        }
-    };
-    insights.push(streak_insight);
-
-    
-    let total_hours = all_time_time / 3600.0;
-    let total_insight = if total_hours >= 1000.0 {
-        Insight {
-            title: "Coding Veteran".to_string(),
-            description: "You've logged over 1000 hours of coding! Incredible dedication!"
-                .to_string(),
-            value: format!("{:.0}h total", total_hours),
-            trend: "Expert level".to_string(),
-            icon: "".to_string(),
-            color: "#FFD700".to_string(),
-        }
-    } else if total_hours >= 100.0 {
-        Insight {
-            title: "Experienced Coder".to_string(),
-            description: "You've put in serious time coding! Keep up the great work!".to_string(),
-            value: format!("{:.0}h total", total_hours),
-            trend: "Strong foundation".to_string(),
-            icon: "".to_string(),
-            color: "#4CAF50".to_string(),
-        }
-    } else if total_hours >= 10.0 {
-        Insight {
-            title: "Learning Journey".to_string(),
-            description: "You're building your coding skills! Every hour counts.".to_string(),
-            value: format!("{:.0}h total", total_hours),
-            trend: "Growing skills".to_string(),
-            icon: "".to_string(),
-            color: "#2196F3".to_string(),
-        }
-    } else {
-        Insight {
-            title: "Just Getting Started".to_string(),
-            description: "Every expert was once a beginner. Keep coding!".to_string(),
-            value: format!("{:.0}h total", total_hours),
-            trend: "Beginning journey".to_string(),
-            icon: "".to_string(),
-            color: "#9C27B0".to_string(),
-        }
-    };
-    insights.push(total_insight);
+fn generate_insights(context: &StatsContext, rules: &RuleSet) -> Vec<Insight> {
+    evaluate(&rules.insights, context)
+        .into_iter()
+        .map(|matched| Insight {
+            id: matched.id,
+            severity: matched.severity,
+            title: matched.title,
+            description: matched.description,
+            value: matched.value,
+            trend: matched.trend,
+            icon: matched.icon,
+            color: matched.color,
+        })
+        .collect()
+}
 
-    insights
+fn default_programmer_class() -> ProgrammerClass {
+    ProgrammerClass {
+        class_name: t("class.default.name"),
+        description: t("class.default.description"),
+        technologies: vec![
+            "HTML".to_string(),
+            "CSS".to_string(),
+            "JavaScript".to_string(),
+        ],
+        level: t("class.default.level"),
+        color: "#9C27B0".to_string(),
+    }
 }
 
 async fn analyze_programmer_class(dashboard_stats: &serde_json::Value) -> ProgrammerClass {
@@ -943,18 +724,7 @@ async fn analyze_programmer_class(dashboard_stats: &serde_json::Value) -> Progra
         Ok(content) => content,
         Err(_) => {
             
-            return ProgrammerClass {
-                class_name: "Code Explorer".to_string(),
-                description: "An enthusiastic learner discovering the vast world of programming."
-                    .to_string(),
-                technologies: vec![
-                    "HTML".to_string(),
-                    "CSS".to_string(),
-                    "JavaScript".to_string(),
-                ],
-                level: "Learning".to_string(),
-                color: "#9C27B0".to_string(),
-            };
+            return default_programmer_class();
         }
     };
 
@@ -962,18 +732,7 @@ async fn analyze_programmer_class(dashboard_stats: &serde_json::Value) -> Progra
         Ok(config) => config,
         Err(_) => {
             
-            return ProgrammerClass {
-                class_name: "Code Explorer".to_string(),
-                description: "An enthusiastic learner discovering the vast world of programming."
-                    .to_string(),
-                technologies: vec![
-                    "HTML".to_string(),
-                    "CSS".to_string(),
-                    "JavaScript".to_string(),
-                ],
-                level: "Learning".to_string(),
-                color: "#9C27B0".to_string(),
-            };
+            return default_programmer_class();
         }
     };
 
@@ -1011,7 +770,7 @@ async fn analyze_programmer_class(dashboard_stats: &serde_json::Value) -> Progra
     
     if let Some(class) = best_match {
         ProgrammerClass {
-            class_name: class["name"].as_str().unwrap_or("Unknown").to_string(),
+            class_name: class["name"].as_str().map(str::to_string).unwrap_or_else(|| t("stats.unknown")),
             description: class["description"].as_str().unwrap_or("").to_string(),
             technologies: class["technologies"]
                 .as_array()
@@ -1020,23 +779,12 @@ async fn analyze_programmer_class(dashboard_stats: &serde_json::Value) -> Progra
                 .filter_map(|t| t.as_str())
                 .map(|s| s.to_string())
                 .collect(),
-            level: class["level"].as_str().unwrap_or("Unknown").to_string(),
+            level: class["level"].as_str().map(str::to_string).unwrap_or_else(|| t("stats.unknown")),
             color: class["color"].as_str().unwrap_or("#9C27B0").to_string(),
         }
     } else {
         
-        ProgrammerClass {
-            class_name: "Code Explorer".to_string(),
-            description: "An enthusiastic learner discovering the vast world of programming."
-                .to_string(),
-            technologies: vec![
-                "HTML".to_string(),
-                "CSS".to_string(),
-                "JavaScript".to_string(),
-            ],
-            level: "Learning".to_string(),
-            color: "#9C27B0".to_string(),
-        }
+        default_programmer_class()
     }
 }
 
@@ -0,0 +1,22 @@
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+static GLOBAL_STATE: Mutex<()> = Mutex::new(());
+
+static TEST_HOME: Lazy<PathBuf> = Lazy::new(|| {
+    let home = std::env::temp_dir().join(format!("hackatime-tests-{}", std::process::id()));
+    std::fs::create_dir_all(&home).expect("failed to create test home");
+    std::env::set_var("HOME", &home);
+    std::env::set_var("APPDATA", &home);
+    std::env::remove_var("WAKATIME_HOME");
+    home
+});
+
+// Points HOME at a per-run temporary directory so config, logs and rule files never touch
+// the real user profile, and serializes tests that share process-wide state.
+pub fn isolated() -> MutexGuard<'static, ()> {
+    let guard = GLOBAL_STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    Lazy::force(&TEST_HOME);
+    guard
+}
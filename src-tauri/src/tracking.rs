@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+
+use crate::database::get_hackatime_config_dir;
+use crate::push_log;
+use crate::setup::{get_wakatime_setting, set_wakatime_setting};
+
+const DISABLED_KEY: &str = "disabled";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackingPause {
+    pub paused_at: i64,
+    pub paused_until: i64,
+    pub previous_disabled: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TrackingStatus {
+    pub is_paused: bool,
+    pub paused_until: Option<i64>,
+    pub remaining_seconds: i64,
+}
+
+impl TrackingStatus {
+    fn from_pause(pause: Option<&TrackingPause>) -> Self {
+        match pause {
+            Some(pause) => Self {
+                is_paused: true,
+                paused_until: Some(pause.paused_until),
+                remaining_seconds: (pause.paused_until - chrono::Utc::now().timestamp()).max(0),
+            },
+            None => Self {
+                is_paused: false,
+                paused_until: None,
+                remaining_seconds: 0,
+            },
+        }
+    }
+}
+
+fn get_pause_path() -> Result<PathBuf, String> {
+    let config_dir = get_hackatime_config_dir()?;
+    Ok(config_dir.join("tracking_pause.json"))
+}
+
+fn load_pause() -> Result<Option<TrackingPause>, String> {
+    let path = get_pause_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read pause state: {}", e))?;
+    let pause = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse pause state: {}", e))?;
+
+    Ok(Some(pause))
+}
+
+fn save_pause(pause: &TrackingPause) -> Result<(), String> {
+    let path = get_pause_path()?;
+    let contents = serde_json::to_string_pretty(pause)
+        .map_err(|e| format!("Failed to serialize pause state: {}", e))?;
+
+    fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write pause state: {}", e))
+}
+
+fn clear_pause() -> Result<(), String> {
+    let path = get_pause_path()?;
+    if path.exists() {
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove pause state: {}", e))?;
+    }
+    Ok(())
+}
+
+pub fn pause_tracking_for(app: &AppHandle, minutes: u64) -> Result<TrackingStatus, String> {
+    if minutes == 0 || minutes > 24 * 60 {
+        return Err("Pause duration must be between 1 minute and 24 hours".to_string());
+    }
+
+    // Re-pausing extends the existing pause, so keep the value that was there before the first one.
+    let previous_disabled = match load_pause()? {
+        Some(existing) => existing.previous_disabled,
+        None => get_wakatime_setting(DISABLED_KEY)?,
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let pause = TrackingPause {
+        paused_at: now,
+        paused_until: now + (minutes as i64) * 60,
+        previous_disabled,
+    };
+
+    save_pause(&pause)?;
+    set_wakatime_setting(DISABLED_KEY, Some("true"))?;
+    push_log("info", "backend", format!("Tracking paused for {} minutes", minutes));
+
+    schedule_auto_resume(app, pause.paused_until);
+
+    let status = TrackingStatus::from_pause(Some(&pause));
+    let _ = app.emit("tracking-status-changed", &status);
+    Ok(status)
+}
+
+pub fn resume_tracking_now(app: &AppHandle) -> Result<TrackingStatus, String> {
+    let Some(pause) = load_pause()? else {
+        return Ok(TrackingStatus::from_pause(None));
+    };
+
+    set_wakatime_setting(DISABLED_KEY, pause.previous_disabled.as_deref())?;
+    clear_pause()?;
+    push_log("info", "backend", "Tracking resumed".to_string());
+
+    let status = TrackingStatus::from_pause(None);
+    let _ = app.emit("tracking-status-changed", &status);
+    Ok(status)
+}
+
+fn schedule_auto_resume(app: &AppHandle, paused_until: i64) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let wait = (paused_until - chrono::Utc::now().timestamp()).max(0) as u64;
+        tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+
+        // A later pause or a manual resume replaces the file, in which case this timer is stale.
+        match load_pause() {
+            Ok(Some(pause)) if pause.paused_until == paused_until => {
+                if let Err(e) = resume_tracking_now(&app_handle) {
+                    push_log("error", "backend", format!("Failed to auto-resume tracking: {}", e));
+                }
+            }
+            Ok(_) => {}
+            Err(e) => push_log("error", "backend", format!("Failed to check pause state: {}", e)),
+        }
+    });
+}
+
+pub fn restore_tracking_pause(app: &AppHandle) {
+    match load_pause() {
+        Ok(Some(pause)) => {
+            if pause.paused_until <= chrono::Utc::now().timestamp() {
+                push_log("info", "backend", "Tracking pause expired while the app was closed, resuming".to_string());
+                if let Err(e) = resume_tracking_now(app) {
+                    push_log("error", "backend", format!("Failed to resume tracking: {}", e));
+                }
+            } else {
+                push_log("info", "backend", format!("Tracking is paused until {}", pause.paused_until));
+                schedule_auto_resume(app, pause.paused_until);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => push_log("error", "backend", format!("Failed to load pause state: {}", e)),
+    }
+}
+
+pub fn handle_tracking_menu_action(app: &AppHandle, action: &str) {
+    let result = match action {
+        "pause_15" => pause_tracking_for(app, 15),
+        "pause_30" => pause_tracking_for(app, 30),
+        "pause_60" => pause_tracking_for(app, 60),
+        "resume" => resume_tracking_now(app),
+        _ => return,
+    };
+
+    if let Err(e) = result {
+        push_log("error", "backend", format!("Tracking menu action '{}' failed: {}", action, e));
+    }
+}
+
+#[tauri::command]
+pub fn pause_tracking(app: AppHandle, minutes: u64) -> Result<TrackingStatus, String> {
+    pause_tracking_for(&app, minutes)
+}
+
+#[tauri::command]
+pub fn resume_tracking(app: AppHandle) -> Result<TrackingStatus, String> {
+    resume_tracking_now(&app)
+}
+
+#[tauri::command]
+pub fn get_tracking_status() -> Result<TrackingStatus, String> {
+    let pause = load_pause()?;
+    Ok(TrackingStatus::from_pause(pause.as_ref()))
+}
@@ -1,38 +1,74 @@
-use tauri::{AppHandle, Manager};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, Wry};
+use tauri::image::Image;
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 
+use crate::auth::AuthState;
+use crate::config::ApiConfig;
+use crate::format::format_duration;
+use crate::i18n::{t, t_with};
+use crate::preferences::load_preferences;
+use crate::push_log;
+use crate::session::SessionState;
+
+pub const TRAY_ID: &str = "main";
+
+const TRAY_ICON_SIZE: u32 = 32;
+const TRAY_REFRESH_INTERVAL_SECS: u64 = 60;
+const ACTIVE_COLOR: [u8; 3] = [0xFB, 0x4B, 0x20];
+const IDLE_COLOR: [u8; 3] = [0x9E, 0x9E, 0x9E];
+const TRACK_COLOR: [u8; 4] = [0x80, 0x80, 0x80, 0x70];
+
+static TRAY_STATUS_SHOWN: AtomicBool = AtomicBool::new(false);
+
+fn tray_status_text(session: &SessionState) -> String {
+    if session.is_active {
+        let project = session.project.clone().unwrap_or_else(|| t("tray.unknown_project"));
+        t_with("tray.status_active", &[("project", project)])
+    } else {
+        t("tray.no_session")
+    }
+}
+
+fn build_tray_menu(app: &AppHandle, status_text: &str) -> tauri::Result<Menu<Wry>> {
+    let status_item = MenuItem::with_id(app, "status", status_text, false, None::<&str>)?;
+    let pause_15_item = MenuItem::with_id(app, "tray_tracking_pause_15", t("tray.pause_15"), true, None::<&str>)?;
+    let pause_60_item = MenuItem::with_id(app, "tray_tracking_pause_60", t("tray.pause_60"), true, None::<&str>)?;
+    let resume_item = MenuItem::with_id(app, "tray_tracking_resume", t("tray.resume"), true, None::<&str>)?;
+    let quit_item = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?;
+
+    Menu::with_items(app, &[
+        &status_item,
+        &tauri::menu::PredefinedMenuItem::separator(app)?,
+        &pause_15_item,
+        &pause_60_item,
+        &resume_item,
+        &tauri::menu::PredefinedMenuItem::separator(app)?,
+        &quit_item,
+    ])
+}
+
 pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     let status_text = {
         let state = app.state::<std::sync::Arc<tauri::async_runtime::Mutex<crate::SessionState>>>();
         let state_arc = state.inner().clone();
         tauri::async_runtime::block_on(async {
             let guard = state_arc.lock().await;
-            if guard.is_active {
-                let project = guard.project.clone().unwrap_or_else(|| "Unknown".to_string());
-                format!("Status: Active — {}", project)
-            } else {
-                "No active session".to_string()
-            }
+            tray_status_text(&guard)
         })
     };
-    let status_item = MenuItem::with_id(app, "status", &status_text, false, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-    
-    let menu = Menu::with_items(app, &[
-        &status_item,
-        &tauri::menu::PredefinedMenuItem::separator(app)?,
-        &quit_item,
-    ])?;
+    let menu = build_tray_menu(app, &status_text)?;
     
     
     let icon = app.default_window_icon()
         .ok_or("No default window icon found")?
         .clone();
     
-    let _tray_icon = TrayIconBuilder::new()
+    let _tray_icon = TrayIconBuilder::with_id(TRAY_ID)
         .icon(icon)
+        .tooltip("Hackatime")
         .menu(&menu)
         .show_menu_on_left_click(true)
         .on_menu_event(|app, event| {
@@ -40,7 +76,11 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
                 "quit" => {
                     app.exit(0);
                 }
-                _ => {}
+                id => {
+                    if let Some(action) = id.strip_prefix("tray_tracking_") {
+                        crate::tracking::handle_tracking_menu_action(app, action);
+                    }
+                }
             }
         })
         .on_tray_icon_event(|tray, event| {
@@ -88,3 +128,173 @@ pub fn setup_tray(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+pub async fn refresh_tray_menu(app: &AppHandle) -> Result<(), String> {
+    let tray = app.tray_by_id(TRAY_ID).ok_or("Tray icon not found")?;
+    let status_text = {
+        let session_state = app.state::<Arc<tauri::async_runtime::Mutex<SessionState>>>();
+        let session = session_state.lock().await;
+        tray_status_text(&session)
+    };
+
+    let menu = build_tray_menu(app, &status_text).map_err(|e| format!("Failed to build tray menu: {}", e))?;
+    tray.set_menu(Some(menu))
+        .map_err(|e| format!("Failed to set tray menu: {}", e))?;
+    Ok(())
+}
+
+pub fn start_tray_status_updater(app: &AppHandle) {
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(TRAY_REFRESH_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            refresh_tray_status(&app_handle).await;
+        }
+    });
+}
+
+pub async fn refresh_tray_status(app: &AppHandle) {
+    let preferences = load_preferences().unwrap_or_default();
+    if !preferences.tray_time_display_enabled {
+        if TRAY_STATUS_SHOWN.swap(false, Ordering::SeqCst) {
+            if let Err(e) = reset_tray_status(app) {
+                push_log("warn", "backend", format!("Failed to reset tray status: {}", e));
+            }
+        }
+        return;
+    }
+
+    let access_token = {
+        let auth_state = app.state::<Arc<tauri::async_runtime::Mutex<AuthState>>>();
+        let auth_state = auth_state.lock().await;
+        if !auth_state.is_authenticated {
+            None
+        } else {
+            auth_state.access_token.clone()
+        }
+    };
+    let Some(access_token) = access_token else {
+        if TRAY_STATUS_SHOWN.swap(false, Ordering::SeqCst) {
+            if let Err(e) = reset_tray_status(app) {
+                push_log("warn", "backend", format!("Failed to reset tray status: {}", e));
+            }
+        }
+        return;
+    };
+
+    let is_active = {
+        let session_state = app.state::<Arc<tauri::async_runtime::Mutex<SessionState>>>();
+        let session = session_state.lock().await;
+        session.is_active
+    };
+
+    let api_config = app.state::<ApiConfig>().inner().clone();
+    let base_url = if api_config.base_url.is_empty() {
+        "https://hackatime.hackclub.com".to_string()
+    } else {
+        api_config.base_url
+    };
+
+    let today = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+    let client = reqwest::Client::new();
+    let today_seconds = match crate::statistics::fetch_hours_with_cache(&client, &base_url, &access_token, &today, &today).await {
+        Ok(data) => data["total_seconds"].as_u64().unwrap_or(0),
+        Err(e) => {
+            push_log("warn", "backend", format!("Failed to fetch today's hours for tray: {}", e));
+            return;
+        }
+    };
+
+    match update_tray_status(app, today_seconds, is_active, preferences.daily_goal_hours) {
+        Ok(()) => TRAY_STATUS_SHOWN.store(true, Ordering::SeqCst),
+        Err(e) => push_log("warn", "backend", format!("Failed to update tray status: {}", e)),
+    }
+}
+
+pub fn update_tray_status(
+    app: &AppHandle,
+    today_seconds: u64,
+    is_active: bool,
+    daily_goal_hours: f64,
+) -> Result<(), String> {
+    let tray = app.tray_by_id(TRAY_ID).ok_or("Tray icon not found")?;
+
+    let text = format_duration(today_seconds as f64);
+    let goal_seconds = (daily_goal_hours * 3600.0).max(1.0);
+    let progress = (today_seconds as f64 / goal_seconds).min(1.0);
+
+    tray.set_icon(Some(render_progress_icon(progress, is_active)))
+        .map_err(|e| format!("Failed to set tray icon: {}", e))?;
+    tray.set_title(Some(&text))
+        .map_err(|e| format!("Failed to set tray title: {}", e))?;
+
+    let tooltip_key = if is_active { "tray.tooltip_coding" } else { "tray.tooltip_today" };
+    let tooltip = t_with(tooltip_key, &[("duration", text)]);
+    tray.set_tooltip(Some(&tooltip))
+        .map_err(|e| format!("Failed to set tray tooltip: {}", e))?;
+
+    Ok(())
+}
+
+fn reset_tray_status(app: &AppHandle) -> Result<(), String> {
+    let tray = app.tray_by_id(TRAY_ID).ok_or("Tray icon not found")?;
+
+    tray.set_icon(app.default_window_icon().cloned())
+        .map_err(|e| format!("Failed to set tray icon: {}", e))?;
+    tray.set_title(None::<&str>)
+        .map_err(|e| format!("Failed to clear tray title: {}", e))?;
+    tray.set_tooltip(Some("Hackatime"))
+        .map_err(|e| format!("Failed to set tray tooltip: {}", e))?;
+
+    Ok(())
+}
+
+
+// Draws a ring that fills clockwise from 12 o'clock as the daily goal is reached,
+// with a filled centre dot while a coding session is active.
+fn render_progress_icon(progress: f64, is_active: bool) -> Image<'static> {
+    let size = TRAY_ICON_SIZE as f64;
+    let center = size / 2.0;
+    let outer_radius = center - 1.0;
+    let inner_radius = outer_radius - 4.0;
+    let dot_radius = inner_radius - 4.0;
+    let ring_color = if is_active { ACTIVE_COLOR } else { IDLE_COLOR };
+
+    let mut rgba = vec![0u8; (TRAY_ICON_SIZE * TRAY_ICON_SIZE * 4) as usize];
+    for y in 0..TRAY_ICON_SIZE {
+        for x in 0..TRAY_ICON_SIZE {
+            let dx = x as f64 + 0.5 - center;
+            let dy = y as f64 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            let ring_coverage = (outer_radius - distance + 0.5).clamp(0.0, 1.0)
+                * (distance - inner_radius + 0.5).clamp(0.0, 1.0);
+            let dot_coverage = if is_active {
+                (dot_radius - distance + 0.5).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            let mut angle = dx.atan2(-dy);
+            if angle < 0.0 {
+                angle += std::f64::consts::TAU;
+            }
+            let in_progress = angle / std::f64::consts::TAU <= progress;
+
+            let pixel = if dot_coverage > 0.0 {
+                [ring_color[0], ring_color[1], ring_color[2], (dot_coverage * 255.0) as u8]
+            } else if ring_coverage > 0.0 && in_progress && progress > 0.0 {
+                [ring_color[0], ring_color[1], ring_color[2], (ring_coverage * 255.0) as u8]
+            } else if ring_coverage > 0.0 {
+                [TRACK_COLOR[0], TRACK_COLOR[1], TRACK_COLOR[2], (ring_coverage * TRACK_COLOR[3] as f64) as u8]
+            } else {
+                [0, 0, 0, 0]
+            };
+
+            let offset = ((y * TRAY_ICON_SIZE + x) * 4) as usize;
+            rgba[offset..offset + 4].copy_from_slice(&pixel);
+        }
+    }
+
+    Image::new_owned(rgba, TRAY_ICON_SIZE, TRAY_ICON_SIZE)
+}